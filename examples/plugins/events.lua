@@ -0,0 +1,22 @@
+-- opens the events of the selected item in the picker
+-- copy into ~/.config/kubectl-select/plugins/ to use
+return {
+  key = "alt-v",
+  description = "Go to Events",
+  mutates = false,
+
+  navigate = function(ctx)
+    if #ctx.names ~= 1 then
+      return nil
+    end
+    return {
+      resource = "events",
+      field_selector = "involvedObject.name=" .. ctx.names[1],
+    }
+  end,
+
+  -- only reached when navigate returns nothing
+  run = function(ctx)
+    return "Can only go to the events of one item at a time\n"
+  end,
+}
@@ -0,0 +1,23 @@
+-- prints the image of every container in the selected pods
+-- copy into ~/.config/kubectl-select/plugins/ to use
+return {
+  key = "alt-g",
+  description = "Images",
+  accepts = {"pods", "pod", "po"},
+  mutates = false,
+
+  run = function(ctx)
+    local pods = kubectl_json(ctx.namespace, "pods", ctx.names)
+    if pods == nil then
+      return "Failed to get pods\n"
+    end
+
+    local lines = {}
+    for _, pod in ipairs(pods) do
+      for _, container in ipairs(pod.spec.containers) do
+        table.insert(lines, pod.metadata.name .. "\t" .. container.name .. "\t" .. container.image)
+      end
+    end
+    return table.concat(lines, "\n") .. "\n"
+  end,
+}
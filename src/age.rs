@@ -0,0 +1,166 @@
+use crate::kubectl::{column_starts, header_columns};
+use std::time::{Duration, Instant};
+
+// columns holding how long ago something happened, which go stale the longer a row is shown
+const AGE_COLUMNS: [&str; 2] = ["AGE", "LAST SEEN"];
+
+// an age the way kubectl prints it
+// example: 3d2h -> 266400s
+pub fn parse(age: &str) -> Option<Duration> {
+    if age.is_empty() {
+        return None;
+    }
+
+    let mut total = 0;
+    let mut number = String::new();
+    for c in age.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 24 * 60 * 60,
+            'y' => 365 * 24 * 60 * 60,
+            _ => return None,
+        };
+        total += number.parse::<u64>().ok()? * unit;
+        number.clear();
+    }
+
+    // a trailing number without a unit isn't an age
+    if number.is_empty() {
+        Some(Duration::from_secs(total))
+    } else {
+        None
+    }
+}
+
+// an age the way kubectl prints it, precise when recent and rounder the older it is
+// example: 266400s -> 3d2h
+pub fn format(age: Duration) -> String {
+    let secs = age.as_secs();
+    let (minutes, hours, days) = (secs / 60, secs / (60 * 60), secs / (24 * 60 * 60));
+    let years = days / 365;
+    let with = |major: u64, major_unit: &str, minor: u64, minor_unit: &str| {
+        if minor == 0 {
+            format!("{}{}", major, major_unit)
+        } else {
+            format!("{}{}{}{}", major, major_unit, minor, minor_unit)
+        }
+    };
+
+    if minutes < 2 {
+        format!("{}s", secs)
+    } else if minutes < 10 {
+        with(minutes, "m", secs % 60, "s")
+    } else if hours < 3 {
+        format!("{}m", minutes)
+    } else if hours < 8 {
+        with(hours, "h", minutes % 60, "m")
+    } else if hours < 48 {
+        format!("{}h", hours)
+    } else if days < 8 {
+        with(days, "d", hours % 24, "h")
+    } else if years < 2 {
+        format!("{}d", days)
+    } else if years < 8 {
+        with(years, "y", days % 365, "d")
+    } else {
+        format!("{}y", years)
+    }
+}
+
+// where the age columns of a listing are, so a row can be shown with its ages brought up to date
+// rather than as old as when it was listed
+pub struct Ages {
+    starts: Vec<usize>,
+    columns: Vec<usize>,
+}
+
+impl Ages {
+    // none when the listing has no age columns
+    pub fn new(header: &str) -> Option<Ages> {
+        let columns: Vec<usize> = header_columns(header)
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| AGE_COLUMNS.contains(&c.as_str()))
+            .map(|(i, _)| i)
+            .collect();
+        if columns.is_empty() || header.contains('\t') {
+            return None;
+        }
+        Some(Ages {
+            starts: column_starts(header),
+            columns,
+        })
+    }
+
+    // the row with each age grown by the time since it was listed, keeping the columns aligned
+    // a cell that isn't an age, like <unknown>, is left as it was
+    pub fn advance(&self, row: &str, listed: Instant) -> Option<String> {
+        let elapsed = listed.elapsed();
+        if elapsed.as_secs() == 0 {
+            return None;
+        }
+
+        let mut chars: Vec<char> = row.chars().collect();
+        // from the right so replacing a cell doesn't move the ones still to do
+        for &column in self.columns.iter().rev() {
+            let start = *self.starts.get(column)?;
+            let end = self
+                .starts
+                .get(column + 1)
+                .map_or(chars.len(), |&e| e.min(chars.len()));
+            if start >= end || (start > 0 && chars[start - 1] != ' ') {
+                continue;
+            }
+
+            let cell: String = chars[start..end].iter().collect();
+            let age = match parse(cell.trim()) {
+                Some(age) => format(age + elapsed),
+                None => continue,
+            };
+            let width = if end == chars.len() {
+                age.len()
+            } else {
+                // keep at least a space before the next column when the age outgrows its cell
+                (end - start).max(age.len() + 1)
+            };
+            chars.splice(
+                start..end,
+                format!("{:<width$}", age, width = width).chars(),
+            );
+        }
+        Some(chars.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_adds_up_units() {
+        assert_eq!(parse("3d2h"), Some(Duration::from_secs(266400)));
+        assert_eq!(parse("45s"), Some(Duration::from_secs(45)));
+        assert_eq!(parse("1y"), Some(Duration::from_secs(365 * 24 * 60 * 60)));
+    }
+
+    #[test]
+    fn parse_refuses_what_isnt_an_age() {
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("5"), None);
+        assert_eq!(parse("3d2"), None);
+        assert_eq!(parse("<unknown>"), None);
+    }
+
+    #[test]
+    fn format_rounds_the_older_it_is() {
+        assert_eq!(format(Duration::from_secs(90)), "90s");
+        assert_eq!(format(Duration::from_secs(266400)), "3d2h");
+        assert_eq!(format(Duration::from_secs(30 * 24 * 60 * 60)), "30d");
+    }
+}
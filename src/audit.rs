@@ -0,0 +1,155 @@
+use crate::kubectl::{current_context, shell_quote};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use subprocess::ExitStatus;
+
+// append-only trail of the kubectl commands run by mutating bindings
+// each command is one json line with when it ran, against which cluster, from which binding on which
+// of the items and how it exited, and the commands of one binding run share when it started
+
+// where to append records, nothing is recorded without one
+static LOG: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+// the mutating binding currently running, commands are only recorded while there is one
+static ACTION: Mutex<Option<Action>> = Mutex::new(None);
+
+struct Action {
+    started: String,
+    binding: String,
+    context: String,
+    namespace: String,
+    resource: String,
+    names: Vec<String>,
+}
+
+pub fn set_log(path: Option<PathBuf>) {
+    *LOG.lock().unwrap() = path;
+}
+
+// starts recording commands on behalf of a mutating binding
+// namespace is the one passed to the tool, the context's own namespace is used otherwise
+pub fn begin(binding: &str, namespace: Option<&str>, resource: &str, names: &[String]) {
+    if LOG.lock().unwrap().is_none() {
+        return;
+    }
+
+    // looked up before the action starts so the lookup itself isn't recorded
+    let (context, default_namespace) = current_context().unwrap_or_default();
+    *ACTION.lock().unwrap() = Some(Action {
+        started: chrono::Utc::now().to_rfc3339(),
+        binding: binding.to_string(),
+        context,
+        namespace: namespace.map_or(default_namespace, str::to_string),
+        resource: resource.to_string(),
+        names: names.to_vec(),
+    });
+}
+
+// stops recording commands once the binding has finished
+pub fn end() {
+    *ACTION.lock().unwrap() = None;
+}
+
+// appends a record of a finished command if a mutating binding is running
+// status is None when the command couldn't be started at all
+pub fn record(cmdline: &str, status: Option<&ExitStatus>) {
+    let action = ACTION.lock().unwrap();
+    let action = match action.as_ref() {
+        Some(action) => action,
+        None => return,
+    };
+    let path = match LOG.lock().unwrap().clone() {
+        Some(path) => path,
+        None => return,
+    };
+
+    // the items the command was run on, or all of them for a command that doesn't name them
+    let names: Vec<&String> = action
+        .names
+        .iter()
+        .filter(|name| cmdline.split(' ').any(|word| word == shell_quote(name)))
+        .collect();
+    let names = if names.is_empty() {
+        action.names.iter().collect()
+    } else {
+        names
+    };
+
+    let exit_status = match status {
+        Some(ExitStatus::Exited(code)) => json!(code),
+        Some(status) => json!(format!("{:?}", status)),
+        None => json!("failed to start"),
+    };
+    let entry = json!({
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "run": action.started,
+        "context": action.context,
+        "namespace": action.namespace,
+        "binding": action.binding,
+        "resource": action.resource,
+        "names": names,
+        "command": cmdline,
+        "exit_status": exit_status,
+    });
+
+    // the action has already happened so a broken log is reported rather than failing it
+    let written = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| writeln!(file, "{}", entry));
+    if let Err(e) = written {
+        eprintln!("Failed to write audit log {}: {}", path.display(), e);
+    }
+}
+
+// a mutating binding run as recorded in the log, to be undone
+#[derive(Deserialize)]
+pub struct Recorded {
+    // records from before runs were logged each stand alone
+    #[serde(default)]
+    run: String,
+    pub binding: String,
+    pub context: String,
+    pub namespace: String,
+    pub resource: String,
+    pub names: Vec<String>,
+    exit_status: Value,
+}
+
+// the last binding in the log that got at least one of its commands through, with the items of
+// the commands that did
+// records from before names were logged are passed over since they can't be undone
+pub fn last_action() -> Result<Recorded, String> {
+    let path = LOG
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("Nothing to undo without an audit-log in the config")?;
+    let contents = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read audit log {}: {}", path.display(), e))?;
+
+    let records: Vec<Recorded> = contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    let mut succeeded = records.into_iter().filter(|r| r.exit_status == json!(0));
+    let mut last = succeeded
+        .next_back()
+        .ok_or("Nothing to undo in the audit log")?;
+    let run = last.run.clone();
+    if !run.is_empty() {
+        for record in succeeded.filter(|r| r.run == run) {
+            for name in record.names {
+                if !last.names.contains(&name) {
+                    last.names.push(name);
+                }
+            }
+        }
+    }
+    Ok(last)
+}
@@ -1,9 +1,27 @@
-use crate::kubectl::kubectl_base_cmd;
-use clipboard::{ClipboardContext, ClipboardProvider};
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
+use crate::clip;
+use crate::config::Confirm;
+use crate::kubectl::{
+    clean, current_context, dry_run, execute, execute_streamed, execute_with_input, forget_failure,
+    global_args, helm_args, interactive, is_dry_run, json_items, kubectl_base_cmd,
+    kubectl_get_clean, kubectl_get_json, label_selector, shell_quote, stream, throttle,
 };
+use crate::pager;
+use crate::picker;
+use crate::progress::{self, Progress};
+use crate::resources;
+use crate::supervisor;
+use crate::theme;
+use regex::{Captures, Regex};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use subprocess::{CaptureData, Exec, Redirection};
+use tabwriter::TabWriter;
 
 // trait for being a key binding action that can be run after skim
 // provides the infomation needed to fully describe and action a binding
@@ -19,6 +37,13 @@ pub trait Binding {
     // the human readable name of the action
     fn description(&self) -> String;
 
+    // the name of the action for referring to it outside of skim, such as with --action
+    // defaults to the description in kebab case
+    // example: Go to Node -> go-to-node
+    fn name(&self) -> String {
+        self.description().to_lowercase().replace(' ', "-")
+    }
+
     // a list of resource types that this binding works for
     // return an empty list for working on all resource types
     // currently have to provide all short names for resource type
@@ -26,9 +51,71 @@ pub trait Binding {
     // there are some common ones as a helper on the binding context type
     fn accepts(&self) -> Vec<String>;
 
+    // whether the binding reads the split columns of each selected item
+    // bindings that only need names should return false to skip parsing every row
+    fn needs_columns(&self) -> bool {
+        true
+    }
+
+    // whether the binding changes the state of the selected items
+    // mutating bindings get a summary of the new state printed after they run
+    fn mutates(&self) -> bool {
+        false
+    }
+
+    // the most items the binding will run on at once, so a slip of the selection can't take out
+    // a whole namespace
+    fn max_items(&self) -> Option<usize> {
+        None
+    }
+
+    // whether the output is for other programs to read, like names for xargs or json for jq
+    // it's printed as it is rather than paged, and items from several contexts or namespaces are
+    // put together without a label line for each
+    fn structured_output(&self) -> bool {
+        false
+    }
+
+    // how to ask before running the binding, for ones a stray key could do harm with
+    // the config can ask for any binding or stop asking, and --yes skips it
+    fn requires_confirmation(&self) -> Confirm {
+        Confirm::Never
+    }
+
+    // what to point out when asking before running the binding
+    fn warning(&self) -> Option<String> {
+        None
+    }
+
+    // the selected items to list when asking before running the binding, each with whatever is
+    // worth knowing about it first, or None to list them as they were selected
+    fn describe_items(&self, _ctx: &BindingContext) -> Option<Vec<String>> {
+        None
+    }
+
+    // whether the kubectl command the binding runs takes every selected name at once
+    // bindings that batch are run in one call rather than one per item, through run_names
+    fn batches(&self) -> bool {
+        false
+    }
+
+    // bindings that relaunch the picker on a different listing return where to go
+    // when this returns none the output of run is shown instead, so run should explain why
+    fn navigate(&self, _ctx: &BindingContext) -> Option<Navigation> {
+        None
+    }
+
+    // whether the binding hands the resource type to kubectl, so can't work on helm releases
+    fn kubectl_only(&self) -> bool {
+        false
+    }
+
     fn runs_for(&self, resource: &str) -> bool {
+        if self.kubectl_only() && resources::is_one_of(resource, &BindingContext::accepts_helm()) {
+            return false;
+        }
         let accepts = self.accepts();
-        accepts.is_empty() || accepts.iter().any(|r| r == resource)
+        accepts.is_empty() || resources::is_one_of(resource, &accepts)
     }
 
     fn preview(&self) -> String {
@@ -37,22 +124,67 @@ pub trait Binding {
         } else {
             self.key()
         };
-        format!(
-            "\x1b[31m{}\t\x1b[33m{}\x1b[0m",
-            self.description(),
-            key_repr
-        )
+        theme::binding_preview(&self.description(), &key_repr)
     }
 }
 
+// a listing for the picker to relaunch on after a navigation binding
+// names, selector and field selector narrow down the kubectl get of the resource
+// history navigations ignore everything else and move through the listings already visited
+#[derive(Default)]
+pub struct Navigation {
+    pub resource: String,
+    pub namespace: Option<String>,
+    // lists from every namespace like -A, in place of the namespace
+    pub all_namespaces: bool,
+    pub names: Vec<String>,
+    pub selector: Option<String>,
+    pub field_selector: Option<String>,
+    pub query: Vec<String>,
+    // flags passed through to kubectl get, kept for going back to the listing they were given for
+    pub get_args: Vec<String>,
+    // the items selected when the listing was left, by their leading columns, to select again
+    // on coming back to it
+    pub selected: Vec<String>,
+    pub history: Option<History>,
+}
+
+pub enum History {
+    Back,
+    Forward,
+    // stays on the current listing with the rows sorted by a column
+    Sort(String),
+    // stays on the current listing switching between every row and only those that restarted
+    Restarting,
+    // stays on the current listing switching between wide output and not
+    Wide,
+    // stays on the current listing showing or hiding the labels column
+    Labels,
+    // stays on the current listing scrolling its columns left or right
+    Scroll(isize),
+    // asks which recent selection in the current context to list again
+    Recent,
+    // saves the selected items of the current listing and its query as a bookmark
+    Bookmark,
+    // asks which bookmark in the current context to jump to
+    Bookmarks,
+    // stays on the current listing as it was
+    Stay,
+}
+
 // provides the binding trait implementations with some context for running
 // this includes namespace, resource and a list of select names, plus vec of columns for each item
+// columns are left empty for bindings that don't need them
 pub struct BindingContext {
+    // the context the items are from when listing from several, otherwise the one kubectl uses
+    pub context: Option<String>,
     pub namespace: Option<String>,
     pub resource: String,
 
     pub names: Vec<String>,
     pub columns: Vec<Vec<String>>,
+    // the header of the columns, without the context and namespace columns taken off the rows
+    pub header: Vec<String>,
 }
 
 impl BindingContext {
@@ -70,14 +202,188 @@ impl BindingContext {
     pub fn accepts_service_accounts() -> Vec<String> {
         vec!["serviceaccounts".into(), "sa".into()]
     }
+
+    #[allow(dead_code)]
+    pub fn accepts_workloads() -> Vec<String> {
+        vec![
+            "deployments".into(),
+            "deployment".into(),
+            "deploy".into(),
+            "statefulsets".into(),
+            "statefulset".into(),
+            "sts".into(),
+            "daemonsets".into(),
+            "daemonset".into(),
+            "ds".into(),
+            "replicasets".into(),
+            "replicaset".into(),
+            "rs".into(),
+        ]
+    }
+
+    pub fn accepts_deployments() -> Vec<String> {
+        vec!["deployments".into(), "deployment".into(), "deploy".into()]
+    }
+
+    pub fn accepts_cronjobs() -> Vec<String> {
+        vec!["cronjobs".into(), "cronjob".into(), "cj".into()]
+    }
+
+    pub fn accepts_jobs() -> Vec<String> {
+        vec!["jobs".into(), "job".into()]
+    }
+
+    #[allow(dead_code)]
+    pub fn accepts_services() -> Vec<String> {
+        vec!["services".into(), "service".into(), "svc".into()]
+    }
+
+    #[allow(dead_code)]
+    pub fn accepts_events() -> Vec<String> {
+        vec!["events".into(), "event".into(), "ev".into()]
+    }
+
+    #[allow(dead_code)]
+    pub fn accepts_secrets() -> Vec<String> {
+        vec!["secrets".into(), "secret".into()]
+    }
+
+    // helm releases aren't a kubernetes resource but are listed and acted on through helm
+    pub fn accepts_helm() -> Vec<String> {
+        vec!["helm".into(), "releases".into(), "release".into()]
+    }
 }
 
 // Names returns all the names of the selected items
-pub struct Names;
+// one per line unless a different delimiter is given, as json with the columns of each item,
+// or each item filled into a template
+pub struct Names {
+    delimiter: String,
+    format: NamesFormat,
+}
+
+enum NamesFormat {
+    Names,
+    Json,
+    Template(String),
+}
+
+impl Names {
+    pub fn new(delimiter: String) -> Self {
+        Names {
+            delimiter,
+            format: NamesFormat::Names,
+        }
+    }
+
+    // example: [{"name": "api-0", "context": null, "namespace": "default", "resource": "pods", "columns": {"NAME": "api-0", ...}}]
+    pub fn json() -> Self {
+        Names {
+            delimiter: "\n".into(),
+            format: NamesFormat::Json,
+        }
+    }
+
+    // a line for each item with {{.field}} filled in from the name, context, namespace, resource and columns
+    pub fn template(delimiter: String, template: String) -> Self {
+        Names {
+            delimiter,
+            format: NamesFormat::Template(template),
+        }
+    }
+
+    fn to_json(&self, ctx: &BindingContext) -> Option<String> {
+        let items: Vec<Value> = ctx
+            .columns
+            .iter()
+            .filter_map(|row| {
+                let columns: serde_json::Map<String, Value> = ctx
+                    .header
+                    .iter()
+                    .zip(row)
+                    .map(|(column, cell)| (column.clone(), Value::String(cell.clone())))
+                    .collect();
+                Some(json!({
+                    "name": row.first()?,
+                    "context": ctx.context,
+                    "namespace": ctx.namespace,
+                    "resource": ctx.resource,
+                    "columns": columns,
+                }))
+            })
+            .collect();
+        serde_json::to_string_pretty(&items)
+            .ok()
+            .map(|json| json + "\n")
+    }
+
+    // columns are fields by their header in lower case with anything but letters and digits as _,
+    // and a field the listing doesn't have is <no value> the way kubectl's go templates print it
+    // example: {{.name}} on {{.node}} -> api-0 on node-a
+    fn to_template(&self, ctx: &BindingContext, template: &str) -> String {
+        let field = Regex::new(r"\{\{\s*\.([A-Za-z0-9_]+)\s*\}\}").unwrap();
+        let lines: Vec<String> = ctx
+            .columns
+            .iter()
+            .map(|row| {
+                let mut fields: HashMap<String, String> = ctx
+                    .header
+                    .iter()
+                    .zip(row)
+                    .map(|(column, cell)| (field_name(column), cell.clone()))
+                    .collect();
+                if let Some(name) = row.first() {
+                    fields.insert("name".into(), name.clone());
+                }
+                fields.insert(
+                    "namespace".into(),
+                    ctx.namespace.clone().unwrap_or_default(),
+                );
+                fields.insert("context".into(), ctx.context.clone().unwrap_or_default());
+                fields.insert("resource".into(), ctx.resource.clone());
+
+                field
+                    .replace_all(template, |caps: &Captures| {
+                        fields
+                            .get(&caps[1])
+                            .cloned()
+                            .unwrap_or_else(|| "<no value>".into())
+                    })
+                    .into_owned()
+            })
+            .collect();
+        lines.join(&self.delimiter)
+    }
+}
+
+// a header as a template field
+// example: NOMINATED NODE -> nominated_node
+fn field_name(column: &str) -> String {
+    column
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+impl Default for Names {
+    fn default() -> Self {
+        Names::new("\n".into())
+    }
+}
 
 impl Binding for Names {
     fn run(&self, ctx: &BindingContext) -> Option<String> {
-        Some(ctx.names.join("\n"))
+        match &self.format {
+            NamesFormat::Names => Some(ctx.names.join(&self.delimiter)),
+            NamesFormat::Json => self.to_json(ctx),
+            NamesFormat::Template(template) => Some(self.to_template(ctx, template)),
+        }
     }
     fn key(&self) -> String {
         "".into()
@@ -88,23 +394,127 @@ impl Binding for Names {
     fn accepts(&self) -> Vec<String> {
         Vec::new()
     }
+    fn needs_columns(&self) -> bool {
+        !matches!(self.format, NamesFormat::Names)
+    }
+    fn structured_output(&self) -> bool {
+        true
+    }
 }
 
 // Json returns a json output of the selected items
 // kubectl get -o json <resource> <items..>
-pub struct Json;
+// when cleaning the server populated fields are stripped first
+// a query extracts fields from each item instead, prefixed by the item's name when there are several
+// kubectl get -o jsonpath=<template> <resource> <item>
+// kubectl get -o json <resource> <item> | jq -c <filter>
+pub struct Json {
+    clean: bool,
+    query: Option<JsonQuery>,
+}
+
+pub enum JsonQuery {
+    JsonPath(String),
+    Jq(String),
+}
+
+impl Json {
+    pub fn new(clean: bool, query: Option<JsonQuery>) -> Self {
+        Json { clean, query }
+    }
+
+    fn jsonpath(&self, ctx: &BindingContext, template: &str) -> Option<String> {
+        let mut output = String::new();
+        for name in &ctx.names {
+            let cmd = kubectl_base_cmd(ctx.namespace.as_deref(), "get", ctx.resource.clone())
+                .arg("--output")
+                .arg(format!("jsonpath={}", template))
+                .arg(name);
+            if let Some(cmdline) = dry_run(&cmd) {
+                output.push_str(&cmdline);
+                continue;
+            }
+            output.push_str(&query_result(ctx, name, &stream(cmd)?));
+        }
+        Some(output)
+    }
+
+    fn jq(&self, ctx: &BindingContext, filter: &str) -> Option<String> {
+        let cmd = kubectl_base_cmd(ctx.namespace.as_deref(), "get", ctx.resource.clone())
+            .arg("--output")
+            .arg("json")
+            .args(&ctx.names);
+        if let Some(cmdline) = dry_run(&cmd) {
+            return Some(format!(
+                "{} | jq -c {}\n",
+                cmdline.trim_end(),
+                shell_quote(filter)
+            ));
+        }
+
+        let mut items = kubectl_get_json(ctx.namespace.as_deref(), &ctx.resource, &ctx.names)?;
+        let mut output = String::new();
+        for item in items.iter_mut() {
+            if self.clean {
+                clean(item);
+            }
+            let name = item["metadata"]["name"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            let result = supervisor::stream(
+                Exec::cmd("jq").arg("-c").arg(filter),
+                Some(&item.to_string()),
+                None,
+                progress::show_line,
+            );
+            progress::clear_line();
+            match result {
+                Some(result) if result.exit_status.success() => {
+                    output.push_str(&query_result(ctx, &name, &result.stdout_str()))
+                }
+                Some(result) => {
+                    return Some(format!("jq failed: {}\n", result.stderr_str().trim()))
+                }
+                None => return Some("Failed to run jq, is it installed?\n".into()),
+            }
+        }
+        Some(output)
+    }
+}
+
+// the output of a query on one item, prefixed by the item's name when several were selected
+fn query_result(ctx: &BindingContext, name: &str, result: &str) -> String {
+    let result = result.trim_end();
+    if ctx.names.len() == 1 {
+        return format!("{}\n", result);
+    }
+    result
+        .lines()
+        .map(|line| format!("{}\t{}\n", name, line))
+        .collect()
+}
 
 impl Binding for Json {
     fn run(&self, ctx: &BindingContext) -> Option<String> {
-        Some(
-            kubectl_base_cmd(ctx.namespace.as_deref(), "get", ctx.resource.clone())
-                .arg("--output")
-                .arg("json")
-                .args(&ctx.names)
-                .capture()
-                .ok()?
-                .stdout_str(),
-        )
+        match &self.query {
+            Some(JsonQuery::JsonPath(template)) => return self.jsonpath(ctx, template),
+            Some(JsonQuery::Jq(filter)) => return self.jq(ctx, filter),
+            None => {}
+        }
+
+        let cmd = kubectl_base_cmd(ctx.namespace.as_deref(), "get", ctx.resource.clone())
+            .arg("--output")
+            .arg("json")
+            .args(&ctx.names);
+        if !self.clean || is_dry_run() {
+            return stream(cmd);
+        }
+
+        let cleaned = kubectl_get_clean(ctx.namespace.as_deref(), &ctx.resource, &ctx.names)?;
+        serde_json::to_string_pretty(&cleaned)
+            .ok()
+            .map(|json| json + "\n")
     }
     fn key(&self) -> String {
         "ctrl-j".into()
@@ -115,23 +525,36 @@ impl Binding for Json {
     fn accepts(&self) -> Vec<String> {
         Vec::new()
     }
+    fn kubectl_only(&self) -> bool {
+        true
+    }
 }
 
 // Yaml returns a yaml output of the selected items
 // kubectl get -o yaml <resource> <items..>
-pub struct Yaml;
+// when cleaning the server populated fields are stripped first
+pub struct Yaml {
+    clean: bool,
+}
+
+impl Yaml {
+    pub fn new(clean: bool) -> Self {
+        Yaml { clean }
+    }
+}
 
 impl Binding for Yaml {
     fn run(&self, ctx: &BindingContext) -> Option<String> {
-        Some(
-            kubectl_base_cmd(ctx.namespace.as_deref(), "get", ctx.resource.clone())
-                .arg("--output")
-                .arg("yaml")
-                .args(&ctx.names)
-                .capture()
-                .ok()?
-                .stdout_str(),
-        )
+        let cmd = kubectl_base_cmd(ctx.namespace.as_deref(), "get", ctx.resource.clone())
+            .arg("--output")
+            .arg("yaml")
+            .args(&ctx.names);
+        if !self.clean || is_dry_run() {
+            return stream(cmd);
+        }
+
+        let cleaned = kubectl_get_clean(ctx.namespace.as_deref(), &ctx.resource, &ctx.names)?;
+        serde_yaml::to_string(&cleaned).ok()
     }
     fn key(&self) -> String {
         "ctrl-y".into()
@@ -142,6 +565,9 @@ impl Binding for Yaml {
     fn accepts(&self) -> Vec<String> {
         Vec::new()
     }
+    fn kubectl_only(&self) -> bool {
+        true
+    }
 }
 
 // Describe returns a kubectl describe output of the selected items
@@ -150,12 +576,9 @@ pub struct Describe;
 
 impl Binding for Describe {
     fn run(&self, ctx: &BindingContext) -> Option<String> {
-        Some(
+        stream(
             kubectl_base_cmd(ctx.namespace.as_deref(), "describe", ctx.resource.clone())
-                .args(&ctx.names)
-                .capture()
-                .ok()?
-                .stdout_str(),
+                .args(&ctx.names),
         )
     }
     fn key(&self) -> String {
@@ -167,23 +590,25 @@ impl Binding for Describe {
     fn accepts(&self) -> Vec<String> {
         Vec::new()
     }
+    fn kubectl_only(&self) -> bool {
+        true
+    }
 }
 
-// Edit returns a kubectl edit output of the selected items
+// Edit opens the selected items in kubectl edit attached to the terminal
 // kubectl edit <resource> <items..>
 pub struct Edit;
 
 impl Binding for Edit {
     fn run(&self, ctx: &BindingContext) -> Option<String> {
-        let tty = std::fs::File::open("/dev/stdout").ok()?;
-        Some(
-            kubectl_base_cmd(ctx.namespace.as_deref(), "edit", ctx.resource.clone())
-                .args(&ctx.names)
-                .stdout(subprocess::Redirection::File(tty))
-                .capture()
-                .ok()?
-                .stdout_str(),
-        )
+        let cmd = kubectl_base_cmd(ctx.namespace.as_deref(), "edit", ctx.resource.clone())
+            .args(&ctx.names);
+        if let Some(cmdline) = dry_run(&cmd) {
+            return Some(cmdline);
+        }
+
+        interactive(cmd)?;
+        None
     }
     fn key(&self) -> String {
         "ctrl-e".into()
@@ -194,41 +619,60 @@ impl Binding for Edit {
     fn accepts(&self) -> Vec<String> {
         Vec::new()
     }
+    fn mutates(&self) -> bool {
+        true
+    }
+    fn kubectl_only(&self) -> bool {
+        true
+    }
+}
+
+// Logs follows the logs of the selected pods
+// several pods are followed at once with each line prefixed by its pod and container
+// with a filter only matching lines are shown, with the matches highlighted
+// kubectl logs <pod> --follow --all-containers
+pub struct Logs {
+    filter: Option<Regex>,
 }
 
-// Edit returns a kubectl logs output of the selected pod
-// kubectl logs <pod..>
-pub struct Logs;
+impl Logs {
+    pub fn new(filter: Option<Regex>) -> Self {
+        Logs { filter }
+    }
+}
 
 impl Binding for Logs {
     fn run(&self, ctx: &BindingContext) -> Option<String> {
-        if ctx.names.len() > 1 {
-            return Some("Cannot get logs of more than one pod at a time".into());
-        }
-
-        let mut cmd = kubectl_base_cmd(ctx.namespace.as_deref(), "logs", None)
-            .arg("--follow")
-            .arg("--all-containers")
-            .args(&ctx.names)
-            .popen()
-            .ok()?;
-
-        let mut comms = cmd.communicate_start(None).limit_size(1024);
+        let prefixed = ctx.names.len() > 1;
+        let cmds: Vec<Exec> = ctx
+            .names
+            .iter()
+            .map(|pod| {
+                let cmd = kubectl_base_cmd(ctx.namespace.as_deref(), "logs", None)
+                    .arg("--follow")
+                    .arg("--all-containers");
+                if prefixed {
+                    cmd.arg("--prefix").arg(pod)
+                } else {
+                    cmd.arg(pod)
+                }
+            })
+            .collect();
 
-        let running = Arc::new(AtomicBool::new(true));
-        let r = running.clone();
-        ctrlc::set_handler(move || {
-            r.store(false, Ordering::SeqCst);
-        })
-        .expect("Error setting Ctrl-C handler");
-
-        while running.load(Ordering::SeqCst) {
-            if let Ok((Some(stdout), _)) = comms.read_string() {
-                println!("{}", stdout);
-            }
+        let cmdlines: Vec<String> = cmds.iter().filter_map(dry_run).collect();
+        if !cmdlines.is_empty() {
+            return Some(cmdlines.concat());
         }
 
-        cmd.terminate().map_err(|_| cmd.kill()).unwrap();
+        // logs stream straight to the terminal until ctrl-c
+        // unless they need to be prefixed or filtered on the way
+        if prefixed || self.filter.is_some() {
+            follow_interleaved(cmds, self.filter.as_ref());
+        } else {
+            throttle();
+            let child = supervisor::spawn(cmds.into_iter().next()?)?;
+            supervisor::follow(child);
+        }
 
         None
     }
@@ -248,9 +692,8 @@ pub struct Copy;
 
 impl Binding for Copy {
     fn run(&self, ctx: &BindingContext) -> Option<String> {
-        let mut clip_ctx: ClipboardContext = ClipboardProvider::new().ok()?;
-        clip_ctx.set_contents(Names.run(ctx)?).ok();
-        None
+        let names = Names::default().run(ctx)?;
+        clip::copy_output(&names, &format!("{} names", ctx.names.len()))
     }
     fn key(&self) -> String {
         "ctrl-space".into()
@@ -261,88 +704,2353 @@ impl Binding for Copy {
     fn accepts(&self) -> Vec<String> {
         Vec::new()
     }
+    fn needs_columns(&self) -> bool {
+        false
+    }
 }
 
-// Cordon returns a kubectl cordon on a node or nodes
-// kubectl cordon node
-pub struct Cordon;
+// CopyRows copies the whole rows of the selected items to the clipboard as an aligned table
+pub struct CopyRows;
 
-impl Binding for Cordon {
+impl Binding for CopyRows {
     fn run(&self, ctx: &BindingContext) -> Option<String> {
-        Some(
-            kubectl_base_cmd(ctx.namespace.as_deref(), "cordon", None)
-                .args(&ctx.names)
-                .capture()
-                .ok()?
-                .stdout_str(),
-        )
+        let rows: Vec<String> = ctx.columns.iter().map(|c| c.join("\t")).collect();
+        clip::copy_output(&tab_align(&rows)?, &format!("{} rows", rows.len()))
     }
     fn key(&self) -> String {
-        "ctrl-k".into()
+        "alt-r".into()
     }
     fn description(&self) -> String {
-        "Cordon".into()
+        "Copy Rows".into()
     }
     fn accepts(&self) -> Vec<String> {
-        BindingContext::accepts_nodes()
+        Vec::new()
     }
 }
 
-// Uncordon returns a kubectl uncordon on a node or nodes
-// kubectl uncordon node
-pub struct Uncordon;
+// CopyYaml copies the yaml of the selected items to the clipboard
+// kubectl get <resource> <items..> -o yaml
+pub struct CopyYaml;
 
-impl Binding for Uncordon {
+impl Binding for CopyYaml {
     fn run(&self, ctx: &BindingContext) -> Option<String> {
-        Some(
-            kubectl_base_cmd(ctx.namespace.as_deref(), "uncordon", None)
-                .args(&ctx.names)
-                .capture()
-                .ok()?
-                .stdout_str(),
-        )
+        let cmd = kubectl_base_cmd(ctx.namespace.as_deref(), "get", ctx.resource.clone())
+            .arg("--output")
+            .arg("yaml")
+            .args(&ctx.names);
+        if let Some(cmdline) = dry_run(&cmd) {
+            return Some(cmdline);
+        }
+
+        let yaml = stream(cmd)?;
+        clip::copy_output(&yaml, &format!("the yaml of {} items", ctx.names.len()))
     }
     fn key(&self) -> String {
-        "ctrl-u".into()
+        "alt-y".into()
     }
     fn description(&self) -> String {
-        "Uncordon".into()
+        "Copy Yaml".into()
     }
     fn accepts(&self) -> Vec<String> {
-        BindingContext::accepts_nodes()
+        Vec::new()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+    fn kubectl_only(&self) -> bool {
+        true
     }
 }
 
-// Column returns the columns of the selected item indexed by the index param
-pub struct Column {
-    name: String,
-    index: usize,
+// CopyColumn asks which column to copy then copies its values for the selected items, one per line
+pub struct CopyColumn {
+    header: Vec<String>,
 }
 
-impl Column {
-    pub fn new(name: String, index: usize) -> Self {
-        Column { name, index }
+impl CopyColumn {
+    pub fn new(header: Vec<String>) -> Self {
+        CopyColumn { header }
     }
 }
 
-impl Binding for Column {
+impl Binding for CopyColumn {
     fn run(&self, ctx: &BindingContext) -> Option<String> {
-        Some(
-            ctx.columns
-                .iter()
-                .filter_map(|c| c.get(self.index))
-                .map(String::from)
-                .collect::<Vec<String>>()
-                .join("\n"),
+        let (name, values) = pick_column(&self.header, ctx)?;
+        clip::copy_output(
+            &values.join("\n"),
+            &format!("{} {} values", values.len(), name),
         )
     }
     fn key(&self) -> String {
-        format!("f{}", self.index)
+        "alt-f".into()
     }
     fn description(&self) -> String {
-        format!("{}:{}", self.index, self.name)
+        "Copy Column".into()
     }
     fn accepts(&self) -> Vec<String> {
         Vec::new()
     }
 }
+
+// PickColumn asks which column to print then prints its values for the selected items, one per line
+// it reaches every column, including those past the function keys
+pub struct PickColumn {
+    header: Vec<String>,
+}
+
+impl PickColumn {
+    pub fn new(header: Vec<String>) -> Self {
+        PickColumn { header }
+    }
+}
+
+impl Binding for PickColumn {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        let (_, values) = pick_column(&self.header, ctx)?;
+        Some(values.join("\n"))
+    }
+    fn key(&self) -> String {
+        "alt-0".into()
+    }
+    fn description(&self) -> String {
+        "Column".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+// asks for one of the header's columns and returns its name and the values of the selected items
+fn pick_column(header: &[String], ctx: &BindingContext) -> Option<(String, Vec<String>)> {
+    let name = picker::pick_one("column", header)?;
+    let index = header.iter().position(|h| *h == name)?;
+    let values = ctx
+        .columns
+        .iter()
+        .filter_map(|c| c.get(index))
+        .cloned()
+        .collect();
+    Some((name, values))
+}
+
+// Sort asks for a column then lists the same items again sorted by it
+// sorting by the column already sorted by reverses the order
+pub struct Sort {
+    header: Vec<String>,
+    current: Option<String>,
+}
+
+impl Sort {
+    pub fn new(header: Vec<String>, current: Option<String>) -> Self {
+        Sort { header, current }
+    }
+}
+
+impl Binding for Sort {
+    fn run(&self, _ctx: &BindingContext) -> Option<String> {
+        None
+    }
+    fn navigate(&self, _ctx: &BindingContext) -> Option<Navigation> {
+        let column = picker::pick_one("sort by", &self.header)?;
+        let sort_by = if self.current.as_deref() == Some(column.as_str()) {
+            format!("-{}", column)
+        } else {
+            column
+        };
+        Some(Navigation {
+            history: Some(History::Sort(sort_by)),
+            ..Default::default()
+        })
+    }
+    fn key(&self) -> String {
+        "ctrl-f".into()
+    }
+    fn description(&self) -> String {
+        "Sort".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        Vec::new()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+}
+
+// Help shows every binding that works for the current resource on a page of its own
+// since the preview is too small to list them all, then returns to the picker
+pub struct Help {
+    rows: Vec<String>,
+    pager: Option<String>,
+}
+
+impl Help {
+    // rows are tab separated and lined up when shown
+    pub fn new(rows: Vec<String>, pager: Option<String>) -> Self {
+        Help { rows, pager }
+    }
+}
+
+impl Binding for Help {
+    fn run(&self, _ctx: &BindingContext) -> Option<String> {
+        None
+    }
+    fn navigate(&self, _ctx: &BindingContext) -> Option<Navigation> {
+        let page = tab_align(&self.rows)?;
+        picker::alternate_screen(true);
+        pager::page_on_tty(&page, self.pager.as_deref());
+        picker::alternate_screen(false);
+        Some(Navigation {
+            history: Some(History::Stay),
+            ..Default::default()
+        })
+    }
+    fn key(&self) -> String {
+        "ctrl-h".into()
+    }
+    fn description(&self) -> String {
+        "Help".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        Vec::new()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+}
+
+// Wide lists the same items again with or without --output wide, such as for the NODE column of pods
+pub struct Wide;
+
+impl Binding for Wide {
+    fn run(&self, _ctx: &BindingContext) -> Option<String> {
+        None
+    }
+    fn navigate(&self, _ctx: &BindingContext) -> Option<Navigation> {
+        Some(Navigation {
+            history: Some(History::Wide),
+            ..Default::default()
+        })
+    }
+    fn key(&self) -> String {
+        "ctrl-w".into()
+    }
+    fn description(&self) -> String {
+        "Toggle Wide".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        Vec::new()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+}
+
+// Labels lists the same items again with or without a LABELS column, making the labels searchable
+pub struct Labels;
+
+impl Binding for Labels {
+    fn run(&self, _ctx: &BindingContext) -> Option<String> {
+        None
+    }
+    fn navigate(&self, _ctx: &BindingContext) -> Option<Navigation> {
+        Some(Navigation {
+            history: Some(History::Labels),
+            ..Default::default()
+        })
+    }
+    fn key(&self) -> String {
+        "ctrl-q".into()
+    }
+    fn description(&self) -> String {
+        "Toggle Labels".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        Vec::new()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+}
+
+// Scroll hides or shows again the columns after the first, for rows too wide for the picker
+pub struct Scroll {
+    by: isize,
+}
+
+impl Scroll {
+    pub fn left() -> Self {
+        Scroll { by: -1 }
+    }
+    pub fn right() -> Self {
+        Scroll { by: 1 }
+    }
+}
+
+impl Binding for Scroll {
+    fn run(&self, _ctx: &BindingContext) -> Option<String> {
+        None
+    }
+    fn navigate(&self, _ctx: &BindingContext) -> Option<Navigation> {
+        Some(Navigation {
+            history: Some(History::Scroll(self.by)),
+            ..Default::default()
+        })
+    }
+    fn key(&self) -> String {
+        if self.by < 0 {
+            "shift-left".into()
+        } else {
+            "shift-right".into()
+        }
+    }
+    fn description(&self) -> String {
+        if self.by < 0 {
+            "Scroll Left".into()
+        } else {
+            "Scroll Right".into()
+        }
+    }
+    fn accepts(&self) -> Vec<String> {
+        Vec::new()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+}
+
+// Restarting toggles between listing every item and only those that have restarted
+pub struct Restarting;
+
+impl Binding for Restarting {
+    fn run(&self, _ctx: &BindingContext) -> Option<String> {
+        None
+    }
+    fn navigate(&self, _ctx: &BindingContext) -> Option<Navigation> {
+        Some(Navigation {
+            history: Some(History::Restarting),
+            ..Default::default()
+        })
+    }
+    fn key(&self) -> String {
+        "ctrl-n".into()
+    }
+    fn description(&self) -> String {
+        "Restarting Only".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        Vec::new()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+}
+
+// CopyCommand asks for a verb then copies a ready to run kubectl command for the selected items
+// kubectl get <resource> --namespace <namespace> <items..> --output yaml
+pub struct CopyCommand;
+
+impl Binding for CopyCommand {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        let mut verbs = vec!["get --output yaml", "describe", "edit", "delete"];
+        if resources::is_one_of(&ctx.resource, &BindingContext::accepts_pods()) {
+            verbs.push("logs");
+        }
+        let verbs: Vec<String> = verbs.into_iter().map(String::from).collect();
+        let chosen = picker::pick_one("command", &verbs)?;
+
+        let mut words = chosen.split_whitespace();
+        let verb = words.next()?;
+        // logs takes pod names on their own rather than a resource type
+        let resource = if verb == "logs" {
+            None
+        } else {
+            Some(ctx.resource.clone())
+        };
+        let cmd = kubectl_base_cmd(ctx.namespace.as_deref(), verb, resource)
+            .args(&ctx.names)
+            .args(&words.collect::<Vec<_>>());
+
+        clip::copy_output(
+            &cmd.to_cmdline_lossy(),
+            &format!("kubectl {} for {} items", verb, ctx.names.len()),
+        )
+    }
+    fn key(&self) -> String {
+        "alt-x".into()
+    }
+    fn description(&self) -> String {
+        "Copy Command".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        Vec::new()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+    fn kubectl_only(&self) -> bool {
+        true
+    }
+}
+
+// Cordon returns a kubectl cordon on a node or nodes
+// kubectl cordon node
+pub struct Cordon;
+
+impl Binding for Cordon {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        run_names(ctx, self.batches(), "Cordoning", |names| {
+            kubectl_base_cmd(ctx.namespace.as_deref(), "cordon", None).args(names)
+        })
+    }
+    fn key(&self) -> String {
+        "ctrl-k".into()
+    }
+    fn description(&self) -> String {
+        "Cordon".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        BindingContext::accepts_nodes()
+    }
+    fn mutates(&self) -> bool {
+        true
+    }
+    fn requires_confirmation(&self) -> Confirm {
+        Confirm::Ask
+    }
+    fn batches(&self) -> bool {
+        true
+    }
+}
+
+// Uncordon returns a kubectl uncordon on a node or nodes
+// kubectl uncordon node
+pub struct Uncordon;
+
+impl Binding for Uncordon {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        run_names(ctx, self.batches(), "Uncordoning", |names| {
+            kubectl_base_cmd(ctx.namespace.as_deref(), "uncordon", None).args(names)
+        })
+    }
+    fn key(&self) -> String {
+        "ctrl-u".into()
+    }
+    fn description(&self) -> String {
+        "Uncordon".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        BindingContext::accepts_nodes()
+    }
+    fn mutates(&self) -> bool {
+        true
+    }
+    fn batches(&self) -> bool {
+        true
+    }
+}
+
+// TriggerJob runs the selected cronjobs now by creating a job from each of them
+// kubectl create job --from cronjob/<item> <item>-manual-<timestamp>
+pub struct TriggerJob;
+
+impl Binding for TriggerJob {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        let timestamp = chrono::Utc::now().timestamp();
+        let mut output = String::new();
+        for cronjob in &ctx.names {
+            // job names are limited to 63 characters
+            let suffix = format!("-manual-{}", timestamp);
+            let base: String = cronjob.chars().take(63 - suffix.len()).collect();
+            output.push_str(&stream(
+                kubectl_base_cmd(ctx.namespace.as_deref(), "create", "job".to_string())
+                    .arg("--from")
+                    .arg(format!("cronjob/{}", cronjob))
+                    .arg(format!("{}{}", base, suffix)),
+            )?);
+        }
+        Some(output)
+    }
+    fn key(&self) -> String {
+        "alt-j".into()
+    }
+    fn description(&self) -> String {
+        "Trigger Job".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        BindingContext::accepts_cronjobs()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+    fn mutates(&self) -> bool {
+        true
+    }
+}
+
+// Suspend stops the selected cronjobs from scheduling new jobs
+// kubectl patch cronjob <item> --type merge --patch {"spec":{"suspend":true}}
+pub struct Suspend;
+
+impl Binding for Suspend {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        patch_suspend(ctx, true)
+    }
+    fn key(&self) -> String {
+        "alt-z".into()
+    }
+    fn description(&self) -> String {
+        "Suspend".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        BindingContext::accepts_cronjobs()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+    fn mutates(&self) -> bool {
+        true
+    }
+    fn requires_confirmation(&self) -> Confirm {
+        Confirm::Ask
+    }
+}
+
+// Resume lets the selected cronjobs schedule jobs again
+// kubectl patch cronjob <item> --type merge --patch {"spec":{"suspend":false}}
+pub struct Resume;
+
+impl Binding for Resume {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        patch_suspend(ctx, false)
+    }
+    fn key(&self) -> String {
+        "alt-u".into()
+    }
+    fn description(&self) -> String {
+        "Resume".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        BindingContext::accepts_cronjobs()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+    fn mutates(&self) -> bool {
+        true
+    }
+}
+
+// patches spec.suspend on each selected cronjob
+fn patch_suspend(ctx: &BindingContext, suspend: bool) -> Option<String> {
+    let patch = json!({"spec": {"suspend": suspend}});
+    patch_each(ctx, "merge", &patch.to_string())
+}
+
+// applies a patch of the given type to each selected item in turn
+// kubectl patch <resource> <item> --type <type> --patch <patch>
+fn patch_each(ctx: &BindingContext, patch_type: &str, patch: &str) -> Option<String> {
+    run_names(ctx, false, "Patching", |names| {
+        kubectl_base_cmd(ctx.namespace.as_deref(), "patch", ctx.resource.clone())
+            .args(names)
+            .arg("--type")
+            .arg(patch_type)
+            .arg("--patch")
+            .arg(patch)
+    })
+}
+
+// runs the kubectl command cmd builds for the selected names, all in one call when batched
+// otherwise, or when kubectl refuses several names as older versions do for some commands,
+// it runs once per item, a few items at once, and carries on past a failed item so the rest still run
+// example: kubectl cordon node-1 node-2, or kubectl cordon node-1 alongside kubectl cordon node-2
+fn run_names<F>(ctx: &BindingContext, batched: bool, action: &str, cmd: F) -> Option<String>
+where
+    F: Fn(&[String]) -> Exec + Sync,
+{
+    if batched && ctx.names.len() > 1 {
+        let cmd = cmd(&ctx.names);
+        if let Some(cmdline) = dry_run(&cmd) {
+            return Some(cmdline);
+        }
+        let result = execute(cmd)?;
+        if !refuses_several_names(&result) {
+            return Some(result.stdout_str());
+        }
+        // the items are tried again one at a time so the refused call isn't a failure of its own
+        forget_failure();
+    }
+
+    // the output is kept in the order of the items rather than the order they finish in
+    let progress = Mutex::new(Progress::new(action, ctx.names.len()));
+    let run = |name: &String| -> String {
+        let cmd = cmd(std::slice::from_ref(name));
+        if let Some(cmdline) = dry_run(&cmd) {
+            return cmdline;
+        }
+
+        let result = execute(cmd);
+        progress.lock().unwrap().step(
+            name,
+            result.as_ref().map_or(false, |r| r.exit_status.success()),
+        );
+        result.map(|r| r.stdout_str()).unwrap_or_default()
+    };
+    let mut output = String::new();
+    for items in ctx.names.chunks(ITEMS_AT_ONCE) {
+        let outputs: Vec<String> = thread::scope(|scope| {
+            let handles: Vec<_> = items
+                .iter()
+                .map(|name| scope.spawn(move || run(name)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap_or_default())
+                .collect()
+        });
+        output.push_str(&outputs.concat());
+    }
+    progress.into_inner().unwrap().finish();
+    Some(output)
+}
+
+// how many items run_names runs a command for at the same time
+// --rate-limit still spaces them out when set
+const ITEMS_AT_ONCE: usize = 8;
+
+// whether kubectl failed because it only takes one name rather than for any of the items
+// example: error: USAGE: cordon NODE [flags]
+fn refuses_several_names(result: &CaptureData) -> bool {
+    let stderr = result.stderr_str();
+    !result.exit_status.success() && (stderr.contains("USAGE:") || stderr.contains("exactly one"))
+}
+
+// PauseRollout stops changes to the selected deployments from rolling out until resumed
+// kubectl rollout pause deployment <items..>
+pub struct PauseRollout;
+
+impl Binding for PauseRollout {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        run_names(ctx, self.batches(), "Pausing", |names| {
+            kubectl_base_cmd(ctx.namespace.as_deref(), "rollout", "pause".to_string())
+                .arg(&ctx.resource)
+                .args(names)
+        })
+    }
+    fn key(&self) -> String {
+        "alt-w".into()
+    }
+    fn description(&self) -> String {
+        "Pause Rollout".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        BindingContext::accepts_deployments()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+    fn mutates(&self) -> bool {
+        true
+    }
+    fn requires_confirmation(&self) -> Confirm {
+        Confirm::Ask
+    }
+    fn batches(&self) -> bool {
+        true
+    }
+}
+
+// ResumeRollout rolls out any changes made to the selected deployments while paused
+// kubectl rollout resume deployment <items..>
+pub struct ResumeRollout;
+
+impl Binding for ResumeRollout {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        run_names(ctx, self.batches(), "Resuming", |names| {
+            kubectl_base_cmd(ctx.namespace.as_deref(), "rollout", "resume".to_string())
+                .arg(&ctx.resource)
+                .args(names)
+        })
+    }
+    fn key(&self) -> String {
+        "alt-m".into()
+    }
+    fn description(&self) -> String {
+        "Resume Rollout".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        BindingContext::accepts_deployments()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+    fn mutates(&self) -> bool {
+        true
+    }
+    fn batches(&self) -> bool {
+        true
+    }
+}
+
+// the binding that reverses what the named binding did, for undo
+// only the ones whose effect can be put back exactly have one
+pub fn inverse(binding: &str) -> Option<Arc<dyn Binding + Send + Sync>> {
+    let inverse: Arc<dyn Binding + Send + Sync> = match binding {
+        "cordon" => Arc::new(Uncordon),
+        "uncordon" => Arc::new(Cordon),
+        "suspend" => Arc::new(Resume),
+        "resume" => Arc::new(Suspend),
+        "pause-rollout" => Arc::new(ResumeRollout),
+        "resume-rollout" => Arc::new(PauseRollout),
+        _ => return None,
+    };
+    Some(inverse)
+}
+
+// Evict evicts the selected pods through the eviction api rather than deleting them
+// so PodDisruptionBudgets are respected, reporting the pods they blocked
+// kubectl create --raw /api/v1/namespaces/<namespace>/pods/<item>/eviction --filename -
+pub struct Evict;
+
+impl Binding for Evict {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        // the namespace of each pod is needed for the eviction path even when it's the current one
+        let pods = kubectl_get_json(ctx.namespace.as_deref(), &ctx.resource, &ctx.names)?;
+
+        let mut output = String::new();
+        let mut progress = Progress::new("Evicting", pods.len());
+        for pod in pods {
+            let name = pod["metadata"]["name"].as_str().unwrap_or_default();
+            let namespace = pod["metadata"]["namespace"].as_str().unwrap_or_default();
+            let eviction = json!({
+                "apiVersion": "policy/v1",
+                "kind": "Eviction",
+                "metadata": {"name": name, "namespace": namespace},
+            });
+
+            let cmd = kubectl_base_cmd(None, "create", None)
+                .arg("--raw")
+                .arg(format!(
+                    "/api/v1/namespaces/{}/pods/{}/eviction",
+                    namespace, name
+                ))
+                .arg("--filename")
+                .arg("-");
+            if let Some(cmdline) = dry_run(&cmd) {
+                output.push_str(&cmdline);
+                continue;
+            }
+
+            let result = execute_with_input(cmd, Some(&eviction.to_string()))?;
+            progress.step(name, result.exit_status.success());
+            let stderr = result.stderr_str();
+            let state = if result.exit_status.success() {
+                "evicted".to_string()
+            } else if stderr.contains("disruption budget") {
+                "blocked by a PodDisruptionBudget".to_string()
+            } else {
+                format!("eviction failed: {}", stderr.trim())
+            };
+            output.push_str(&format!("pod {}: {}\n", name, state));
+        }
+        progress.finish();
+        Some(output)
+    }
+    fn key(&self) -> String {
+        "ctrl-x".into()
+    }
+    fn description(&self) -> String {
+        "Evict".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        BindingContext::accepts_pods()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+    fn mutates(&self) -> bool {
+        true
+    }
+    fn requires_confirmation(&self) -> Confirm {
+        Confirm::Ask
+    }
+}
+
+// ForceDelete deletes the selected pods without waiting for them to terminate, for pods stuck terminating
+// has yes typed first since the containers may still be running on the node
+// kubectl delete pod <items..> --grace-period 0 --force
+pub struct ForceDelete;
+
+impl Binding for ForceDelete {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        stream(
+            kubectl_base_cmd(ctx.namespace.as_deref(), "delete", ctx.resource.clone())
+                .args(&ctx.names)
+                .arg("--grace-period")
+                .arg("0")
+                .arg("--force"),
+        )
+    }
+    fn key(&self) -> String {
+        "alt-d".into()
+    }
+    fn description(&self) -> String {
+        "Force Delete".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        BindingContext::accepts_pods()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+    fn mutates(&self) -> bool {
+        true
+    }
+    fn max_items(&self) -> Option<usize> {
+        Some(10)
+    }
+    fn requires_confirmation(&self) -> Confirm {
+        Confirm::Typed
+    }
+    fn warning(&self) -> Option<String> {
+        Some(
+            "force deleting doesn't wait for the containers to stop, they may keep running on the node"
+                .into(),
+        )
+    }
+    // points out pods that aren't stuck since they may have been selected by mistake
+    fn describe_items(&self, ctx: &BindingContext) -> Option<Vec<String>> {
+        let pods = kubectl_get_json(ctx.namespace.as_deref(), &ctx.resource, &ctx.names)?;
+        Some(
+            pods.iter()
+                .map(|p| {
+                    let name = p["metadata"]["name"].as_str().unwrap_or_default();
+                    if p["metadata"]["deletionTimestamp"].is_null() {
+                        format!("{} (not terminating)", name)
+                    } else {
+                        name.to_string()
+                    }
+                })
+                .collect(),
+        )
+    }
+}
+
+// RemoveFinalizers clears the finalizers of the selected items, for namespaces or custom resources stuck terminating
+// has yes typed first since whatever the finalizers were waiting on to clean up is skipped
+// kubectl patch <resource> <item> --type merge --patch {"metadata":{"finalizers":[]}}
+pub struct RemoveFinalizers;
+
+impl Binding for RemoveFinalizers {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        let patch = json!({"metadata": {"finalizers": []}}).to_string();
+        patch_each(ctx, "merge", &patch)
+    }
+    fn key(&self) -> String {
+        "alt-q".into()
+    }
+    fn description(&self) -> String {
+        "Remove Finalizers".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        vec![]
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+    fn mutates(&self) -> bool {
+        true
+    }
+    fn max_items(&self) -> Option<usize> {
+        Some(10)
+    }
+    fn requires_confirmation(&self) -> Confirm {
+        Confirm::Typed
+    }
+    fn warning(&self) -> Option<String> {
+        Some(
+            "removing finalizers skips the cleanup they guard and can leave orphaned resources behind"
+                .into(),
+        )
+    }
+    // lists the finalizers that will be skipped for each item
+    fn describe_items(&self, ctx: &BindingContext) -> Option<Vec<String>> {
+        let items = kubectl_get_json(ctx.namespace.as_deref(), &ctx.resource, &ctx.names)?;
+        Some(
+            items
+                .iter()
+                .map(|i| {
+                    let finalizers: Vec<&str> = i["metadata"]["finalizers"]
+                        .as_array()
+                        .map(|f| f.iter().filter_map(|f| f.as_str()).collect())
+                        .unwrap_or_default();
+                    format!(
+                        "{}: {}",
+                        i["metadata"]["name"].as_str().unwrap_or_default(),
+                        finalizers.join(", ")
+                    )
+                })
+                .collect(),
+        )
+    }
+    fn kubectl_only(&self) -> bool {
+        true
+    }
+}
+
+// Patch opens an editor on a patch template then applies the patch to every selected item
+// the patch type is picked first: strategic and merge patches are objects, json patches are a list of operations
+// kubectl patch <resource> <item> --type <type> --patch <edited patch>
+pub struct Patch;
+
+impl Binding for Patch {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        let types = ["strategic".to_string(), "merge".into(), "json".into()];
+        let patch_type = match picker::pick_one("patch type", &types) {
+            Some(patch_type) => patch_type,
+            None => return Some("Patch cancelled\n".into()),
+        };
+
+        // yaml since it's easier to edit than json, kubectl converts it
+        let template = if patch_type == "json" {
+            "- op: replace\n  path: /metadata/labels/example\n  value: example\n"
+        } else {
+            "metadata:\n  labels: {}\n  annotations: {}\n"
+        };
+        let header = format!(
+            "# {} patch for {} {}\n# an empty or unchanged patch is cancelled\n",
+            patch_type,
+            ctx.resource,
+            ctx.names.join(" ")
+        );
+
+        let path = supervisor::temp_dir()?.join("patch.yaml");
+        fs::write(&path, format!("{}{}", header, template)).ok()?;
+
+        // the editor may have arguments of its own so leave splitting it to the shell
+        let editor = env::var("KUBE_EDITOR")
+            .or_else(|_| env::var("EDITOR"))
+            .unwrap_or_else(|_| "vi".into());
+        let edited = supervisor::join(Exec::shell(format!("{} {}", editor, shell_quote(&path))))
+            .filter(|status| status.success())
+            .and_then(|_| fs::read_to_string(&path).ok());
+        let _ = fs::remove_file(&path);
+
+        let patch = match edited {
+            Some(edited) => edited.replace(&header, ""),
+            None => return Some("Patch cancelled\n".into()),
+        };
+        if patch.trim().is_empty() || patch == template {
+            return Some("Patch cancelled\n".into());
+        }
+
+        patch_each(ctx, &patch_type, &patch)
+    }
+    fn key(&self) -> String {
+        "ctrl-o".into()
+    }
+    fn description(&self) -> String {
+        "Patch".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        vec![]
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+    fn mutates(&self) -> bool {
+        true
+    }
+    fn kubectl_only(&self) -> bool {
+        true
+    }
+}
+
+// LastApplied prints the configuration the selected items were last applied with, as yaml
+// kubectl get <resource> <items..> -o json, reading the kubectl.kubernetes.io/last-applied-configuration annotation
+pub struct LastApplied;
+
+impl Binding for LastApplied {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        let items = kubectl_get_json(ctx.namespace.as_deref(), &ctx.resource, &ctx.names)?;
+
+        let mut output = String::new();
+        for item in items {
+            let name = item["metadata"]["name"].as_str().unwrap_or_default();
+            match last_applied(&item) {
+                Some(applied) => {
+                    output.push_str(&format!("# {}\n", name));
+                    output.push_str(&serde_yaml::to_string(&applied).ok()?);
+                }
+                None => output.push_str(&format!("# {}: {}\n", name, NOT_APPLIED)),
+            }
+        }
+        Some(output)
+    }
+    fn key(&self) -> String {
+        "ctrl-v".into()
+    }
+    fn description(&self) -> String {
+        "Last Applied".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        vec![]
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+    fn kubectl_only(&self) -> bool {
+        true
+    }
+}
+
+// DiffLastApplied diffs the last applied configuration of the selected items against their live state
+// to show what drifted since the last kubectl apply
+// diff -u <last applied yaml> <live yaml without managedFields>
+pub struct DiffLastApplied;
+
+impl Binding for DiffLastApplied {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        let items = kubectl_get_json(ctx.namespace.as_deref(), &ctx.resource, &ctx.names)?;
+        let dir = supervisor::temp_dir()?;
+
+        let mut output = String::new();
+        for mut item in items {
+            let name = item["metadata"]["name"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            let applied = match last_applied(&item) {
+                Some(applied) => applied,
+                None => {
+                    output.push_str(&format!("{}: {}\n", name, NOT_APPLIED));
+                    continue;
+                }
+            };
+
+            // fields written by the server only add noise, as does the annotation being compared
+            if let Some(metadata) = item["metadata"].as_object_mut() {
+                metadata.remove("managedFields");
+            }
+            if let Some(annotations) = item["metadata"]["annotations"].as_object_mut() {
+                annotations.remove(LAST_APPLIED);
+            }
+
+            let applied_path = dir.join("applied.yaml");
+            let live_path = dir.join("live.yaml");
+            fs::write(&applied_path, serde_yaml::to_string(&applied).ok()?).ok()?;
+            fs::write(&live_path, serde_yaml::to_string(&item).ok()?).ok()?;
+
+            let diff = supervisor::stream(
+                Exec::cmd("diff")
+                    .arg("-u")
+                    .arg("--label")
+                    .arg(format!("{} last applied", name))
+                    .arg("--label")
+                    .arg(format!("{} live", name))
+                    .arg(&applied_path)
+                    .arg(&live_path),
+                None,
+                None,
+                progress::show_line,
+            );
+            progress::clear_line();
+            let _ = fs::remove_file(&applied_path);
+            let _ = fs::remove_file(&live_path);
+
+            // diff exits 1 when there are differences so only an empty diff means nothing changed
+            match diff.map(|d| d.stdout_str()) {
+                Some(diff) if diff.is_empty() => output.push_str(&format!("{}: no drift\n", name)),
+                Some(diff) => output.push_str(&diff),
+                None => output.push_str(&format!("{}: failed to run diff\n", name)),
+            }
+        }
+        Some(output)
+    }
+    fn key(&self) -> String {
+        "ctrl-b".into()
+    }
+    fn description(&self) -> String {
+        "Diff Last Applied".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        vec![]
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+    fn kubectl_only(&self) -> bool {
+        true
+    }
+}
+
+const LAST_APPLIED: &str = "kubectl.kubernetes.io/last-applied-configuration";
+const NOT_APPLIED: &str = "no last applied configuration, it wasn't created with kubectl apply";
+
+// the parsed last applied configuration annotation of an object
+fn last_applied(item: &Value) -> Option<Value> {
+    let applied = item["metadata"]["annotations"][LAST_APPLIED].as_str()?;
+    serde_json::from_str(applied).ok()
+}
+
+// Top returns the resource usage of the selected pods or nodes
+// kubectl top pod <items..> --containers
+// kubectl top node <items..>
+pub struct Top;
+
+impl Binding for Top {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        let cmd = if resources::is_one_of(&ctx.resource, &BindingContext::accepts_nodes()) {
+            kubectl_base_cmd(ctx.namespace.as_deref(), "top", "node".to_string())
+        } else {
+            kubectl_base_cmd(ctx.namespace.as_deref(), "top", "pod".to_string()).arg("--containers")
+        };
+
+        let cmd = cmd.args(&ctx.names);
+        if let Some(cmdline) = dry_run(&cmd) {
+            return Some(cmdline);
+        }
+
+        let output = stream(cmd)?;
+
+        // normalise the spacing into a tab aligned table
+        let rows: Vec<String> = output
+            .lines()
+            .map(|l| l.split_whitespace().collect::<Vec<_>>().join("\t"))
+            .collect();
+        tab_align(&rows)
+    }
+    fn key(&self) -> String {
+        "ctrl-t".into()
+    }
+    fn description(&self) -> String {
+        "Top".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        [
+            BindingContext::accepts_pods(),
+            BindingContext::accepts_nodes(),
+        ]
+        .concat()
+    }
+}
+
+// SecretDecode prints the base64 decoded data keys of the selected secrets
+// kubectl get secret -o json <items..>
+// when redacting only the keys are printed so the values never hit the terminal
+pub struct SecretDecode {
+    redact: bool,
+}
+
+impl SecretDecode {
+    pub fn new(redact: bool) -> Self {
+        SecretDecode { redact }
+    }
+}
+
+impl Binding for SecretDecode {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        let secrets = kubectl_get_json(ctx.namespace.as_deref(), &ctx.resource, &ctx.names)?;
+
+        let mut lines = Vec::new();
+        for secret in secrets {
+            lines.push(format!(
+                "{}:",
+                secret["metadata"]["name"].as_str().unwrap_or_default()
+            ));
+
+            let data = match secret["data"].as_object() {
+                Some(data) => data,
+                None => continue,
+            };
+
+            for (key, value) in data {
+                if self.redact {
+                    lines.push(format!("  {}", key));
+                    continue;
+                }
+
+                let decoded = value
+                    .as_str()
+                    .and_then(|v| base64::decode(v).ok())
+                    .map(|b| String::from_utf8_lossy(&b).into_owned())
+                    .unwrap_or_default();
+                lines.push(format!("  {}: {}", key, decoded));
+            }
+        }
+
+        Some(lines.join("\n"))
+    }
+    fn key(&self) -> String {
+        "ctrl-s".into()
+    }
+    fn description(&self) -> String {
+        "Decode Secret".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        BindingContext::accepts_secrets()
+    }
+}
+
+// Node relaunches the picker on the nodes the selected pods are scheduled to
+pub struct Node;
+
+impl Binding for Node {
+    fn run(&self, _ctx: &BindingContext) -> Option<String> {
+        Some("Selected pods are not scheduled to a node".into())
+    }
+    fn navigate(&self, ctx: &BindingContext) -> Option<Navigation> {
+        let pods = kubectl_get_json(ctx.namespace.as_deref(), &ctx.resource, &ctx.names)?;
+
+        let mut nodes: Vec<String> = pods
+            .iter()
+            .filter_map(|p| p["spec"]["nodeName"].as_str().map(String::from))
+            .collect();
+        nodes.sort();
+        nodes.dedup();
+
+        if nodes.is_empty() {
+            return None;
+        }
+
+        Some(Navigation {
+            resource: "nodes".into(),
+            namespace: ctx.namespace.clone(),
+            names: nodes,
+            ..Default::default()
+        })
+    }
+    fn key(&self) -> String {
+        "alt-n".into()
+    }
+    fn description(&self) -> String {
+        "Go to Node".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        BindingContext::accepts_pods()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+}
+
+// Pods relaunches the picker on the pods scheduled to the selected node or selected by the workload or service
+// kubectl get pods --field-selector spec.nodeName=<node>
+// kubectl get pods --selector <workload or service .spec.selector>
+pub struct Pods;
+
+impl Binding for Pods {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        if ctx.names.len() > 1 {
+            return Some("Cannot go to the pods of more than one item at a time".into());
+        }
+        Some(format!(
+            "Cannot find the pods of {} {}",
+            ctx.resource,
+            ctx.names.join("")
+        ))
+    }
+    fn navigate(&self, ctx: &BindingContext) -> Option<Navigation> {
+        if ctx.names.len() != 1 {
+            return None;
+        }
+
+        if resources::is_one_of(&ctx.resource, &BindingContext::accepts_nodes()) {
+            // a node runs pods from every namespace
+            return Some(Navigation {
+                resource: "pods".into(),
+                all_namespaces: true,
+                field_selector: Some(format!("spec.nodeName={}", ctx.names[0])),
+                ..Default::default()
+            });
+        }
+
+        // workloads and services find their pods through the label selector in their spec
+        let workload =
+            kubectl_get_json(ctx.namespace.as_deref(), &ctx.resource, &ctx.names)?.pop()?;
+
+        Some(Navigation {
+            resource: "pods".into(),
+            namespace: ctx.namespace.clone(),
+            selector: Some(label_selector(&workload["spec"]["selector"])?),
+            ..Default::default()
+        })
+    }
+    fn key(&self) -> String {
+        "alt-p".into()
+    }
+    fn description(&self) -> String {
+        "Go to Pods".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        [
+            BindingContext::accepts_nodes(),
+            BindingContext::accepts_workloads(),
+            BindingContext::accepts_jobs(),
+            BindingContext::accepts_services(),
+        ]
+        .concat()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+}
+
+// Endpoints prints the endpoint slice targets backing the selected services
+// kubectl get endpointslices --selector kubernetes.io/service-name in (<items..>)
+pub struct Endpoints;
+
+impl Binding for Endpoints {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        let cmd = kubectl_base_cmd(
+            ctx.namespace.as_deref(),
+            "get",
+            "endpointslices".to_string(),
+        )
+        .arg("--selector")
+        .arg(format!(
+            "kubernetes.io/service-name in ({})",
+            ctx.names.join(",")
+        ))
+        .arg("--output")
+        .arg("json");
+        if let Some(cmdline) = dry_run(&cmd) {
+            return Some(cmdline);
+        }
+
+        let output = stream(cmd)?;
+
+        let mut rows = vec!["SERVICE\tADDRESSES\tPORTS\tREADY\tTARGET\tNODE".to_string()];
+        for slice in json_items(&output)? {
+            let service = slice["metadata"]["labels"]["kubernetes.io/service-name"]
+                .as_str()
+                .unwrap_or_default();
+            let ports = slice["ports"]
+                .as_array()
+                .map(|ports| {
+                    ports
+                        .iter()
+                        .map(|p| {
+                            format!("{}/{}", p["port"], p["protocol"].as_str().unwrap_or("TCP"))
+                        })
+                        .collect::<Vec<_>>()
+                        .join(",")
+                })
+                .unwrap_or_default();
+
+            for endpoint in slice["endpoints"].as_array().into_iter().flatten() {
+                let addresses = endpoint["addresses"]
+                    .as_array()
+                    .map(|a| {
+                        a.iter()
+                            .filter_map(Value::as_str)
+                            .collect::<Vec<_>>()
+                            .join(",")
+                    })
+                    .unwrap_or_default();
+                let target = &endpoint["targetRef"];
+
+                rows.push(format!(
+                    "{}\t{}\t{}\t{}\t{}/{}\t{}",
+                    service,
+                    addresses,
+                    ports,
+                    endpoint["conditions"]["ready"].as_bool().unwrap_or(true),
+                    target["kind"].as_str().unwrap_or_default().to_lowercase(),
+                    target["name"].as_str().unwrap_or_default(),
+                    endpoint["nodeName"].as_str().unwrap_or_default()
+                ));
+            }
+        }
+
+        tab_align(&rows)
+    }
+    fn key(&self) -> String {
+        "alt-e".into()
+    }
+    fn description(&self) -> String {
+        "Endpoints".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        BindingContext::accepts_services()
+    }
+}
+
+// InvolvedObject relaunches the picker on the object the selected event is about
+// the OBJECT column of an event row looks like pod/<name>
+pub struct InvolvedObject;
+
+impl Binding for InvolvedObject {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        if ctx.columns.len() > 1 {
+            return Some("Cannot go to the object of more than one event at a time".into());
+        }
+        Some("Cannot find the object of the selected event".into())
+    }
+    fn navigate(&self, ctx: &BindingContext) -> Option<Navigation> {
+        if ctx.columns.len() != 1 {
+            return None;
+        }
+
+        let row = &ctx.columns[0];
+        let (kind, name) = row.iter().find_map(|c| {
+            let mut parts = c.splitn(2, '/');
+            Some((parts.next()?, parts.next()?))
+        })?;
+
+        Some(Navigation {
+            resource: kind.to_lowercase(),
+            namespace: ctx.namespace.clone(),
+            names: vec![name.into()],
+            ..Default::default()
+        })
+    }
+    fn key(&self) -> String {
+        "alt-i".into()
+    }
+    fn description(&self) -> String {
+        "Go to Involved Object".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        BindingContext::accepts_events()
+    }
+}
+
+// OwnerChain prints who owns the selected items all the way up
+// follows metadata.ownerReferences, for example pod/api-x-y -> replicaset.apps/api-x -> deployment.apps/api
+pub struct OwnerChain;
+
+impl Binding for OwnerChain {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        let objects = kubectl_get_json(ctx.namespace.as_deref(), &ctx.resource, &ctx.names)?;
+
+        let chains: Vec<String> = objects
+            .into_iter()
+            .map(|object| {
+                let mut chain = vec![format!(
+                    "{}/{}",
+                    object["kind"].as_str().unwrap_or_default().to_lowercase(),
+                    object["metadata"]["name"].as_str().unwrap_or_default()
+                )];
+
+                // owners can't realistically be more than a few levels deep, so stop well before any cycle would matter
+                let mut current = object;
+                while chain.len() < 16 {
+                    let (resource, name) = match owner_of(&current) {
+                        Some(owner) => owner,
+                        None => break,
+                    };
+                    chain.push(format!("{}/{}", resource, name));
+
+                    match kubectl_get_json(ctx.namespace.as_deref(), &resource, &[name])
+                        .and_then(|mut o| o.pop())
+                    {
+                        Some(owner) => current = owner,
+                        None => break,
+                    }
+                }
+
+                chain.join(" -> ")
+            })
+            .collect();
+
+        Some(chains.join("\n"))
+    }
+    fn key(&self) -> String {
+        "alt-c".into()
+    }
+    fn description(&self) -> String {
+        "Owner Chain".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        Vec::new()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+    fn kubectl_only(&self) -> bool {
+        true
+    }
+}
+
+// Owner relaunches the picker on the owner of the selected item
+pub struct Owner;
+
+impl Binding for Owner {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        if ctx.names.len() > 1 {
+            return Some("Cannot go to the owner of more than one item at a time".into());
+        }
+        Some(format!(
+            "{} {} has no owner",
+            ctx.resource,
+            ctx.names.join("")
+        ))
+    }
+    fn navigate(&self, ctx: &BindingContext) -> Option<Navigation> {
+        if ctx.names.len() != 1 {
+            return None;
+        }
+
+        let object =
+            kubectl_get_json(ctx.namespace.as_deref(), &ctx.resource, &ctx.names)?.pop()?;
+        let (resource, name) = owner_of(&object)?;
+
+        Some(Navigation {
+            resource,
+            namespace: ctx.namespace.clone(),
+            names: vec![name],
+            ..Default::default()
+        })
+    }
+    fn key(&self) -> String {
+        "alt-o".into()
+    }
+    fn description(&self) -> String {
+        "Go to Owner".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        Vec::new()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+    fn kubectl_only(&self) -> bool {
+        true
+    }
+}
+
+// the controlling owner of an object as a kubectl resource and name
+// the group is kept from the api version so CRD kinds can't clash with built in ones
+// {kind: ReplicaSet, apiVersion: apps/v1, name: api-x} -> (replicaset.apps, api-x)
+fn owner_of(object: &Value) -> Option<(String, String)> {
+    let references = object["metadata"]["ownerReferences"].as_array()?;
+    let reference = references
+        .iter()
+        .find(|r| r["controller"].as_bool().unwrap_or(false))
+        .or_else(|| references.first())?;
+
+    let kind = reference["kind"].as_str()?.to_lowercase();
+    let resource = match reference["apiVersion"].as_str()?.rsplitn(2, '/').nth(1) {
+        Some(group) => format!("{}.{}", kind, group),
+        None => kind,
+    };
+
+    Some((resource, reference["name"].as_str()?.to_string()))
+}
+
+// Back relaunches the picker on the previous listing with its query restored
+pub struct Back;
+
+impl Binding for Back {
+    fn run(&self, _ctx: &BindingContext) -> Option<String> {
+        None
+    }
+    fn navigate(&self, _ctx: &BindingContext) -> Option<Navigation> {
+        Some(Navigation {
+            history: Some(History::Back),
+            ..Default::default()
+        })
+    }
+    fn key(&self) -> String {
+        "ctrl-left".into()
+    }
+    fn description(&self) -> String {
+        "Back".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        Vec::new()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+}
+
+// Forward undoes a Back
+pub struct Forward;
+
+impl Binding for Forward {
+    fn run(&self, _ctx: &BindingContext) -> Option<String> {
+        None
+    }
+    fn navigate(&self, _ctx: &BindingContext) -> Option<Navigation> {
+        Some(Navigation {
+            history: Some(History::Forward),
+            ..Default::default()
+        })
+    }
+    fn key(&self) -> String {
+        "ctrl-right".into()
+    }
+    fn description(&self) -> String {
+        "Forward".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        Vec::new()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+}
+
+// Recent picks from the items bindings were last run on to list them again
+pub struct Recent;
+
+impl Binding for Recent {
+    fn run(&self, _ctx: &BindingContext) -> Option<String> {
+        None
+    }
+    fn navigate(&self, _ctx: &BindingContext) -> Option<Navigation> {
+        Some(Navigation {
+            history: Some(History::Recent),
+            ..Default::default()
+        })
+    }
+    fn key(&self) -> String {
+        "ctrl-alt-r".into()
+    }
+    fn description(&self) -> String {
+        "Recent".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        Vec::new()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+}
+
+// Bookmark saves the selected items of the listing along with the query, to jump back to later
+pub struct Bookmark;
+
+impl Binding for Bookmark {
+    fn run(&self, _ctx: &BindingContext) -> Option<String> {
+        None
+    }
+    fn navigate(&self, ctx: &BindingContext) -> Option<Navigation> {
+        Some(Navigation {
+            resource: ctx.resource.clone(),
+            namespace: ctx.namespace.clone(),
+            names: ctx.names.clone(),
+            history: Some(History::Bookmark),
+            ..Default::default()
+        })
+    }
+    fn key(&self) -> String {
+        "ctrl-alt-m".into()
+    }
+    fn description(&self) -> String {
+        "Bookmark".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        Vec::new()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+}
+
+// Bookmarks picks a bookmark to jump to
+pub struct Bookmarks;
+
+impl Binding for Bookmarks {
+    fn run(&self, _ctx: &BindingContext) -> Option<String> {
+        None
+    }
+    fn navigate(&self, _ctx: &BindingContext) -> Option<Navigation> {
+        Some(Navigation {
+            history: Some(History::Bookmarks),
+            ..Default::default()
+        })
+    }
+    fn key(&self) -> String {
+        "ctrl-alt-b".into()
+    }
+    fn description(&self) -> String {
+        "Bookmarks".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        Vec::new()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+}
+
+// Column returns the columns of the selected item indexed by the index param
+// each is on a function key, and the first 9 on alt and a number as well
+// they're named by position so the config can remap them whatever the listing's columns are
+// example: column-3 on f3 and alt-column-3 on alt-3
+pub struct Column {
+    name: String,
+    index: usize,
+    alt: bool,
+}
+
+impl Column {
+    pub fn new(name: String, index: usize) -> Self {
+        Column {
+            name,
+            index,
+            alt: false,
+        }
+    }
+
+    pub fn alt(name: String, index: usize) -> Self {
+        Column {
+            name,
+            index,
+            alt: true,
+        }
+    }
+}
+
+impl Binding for Column {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        Some(
+            ctx.columns
+                .iter()
+                .filter_map(|c| c.get(self.index))
+                .map(String::from)
+                .collect::<Vec<String>>()
+                .join("\n"),
+        )
+    }
+    fn key(&self) -> String {
+        if self.alt {
+            format!("alt-{}", self.index)
+        } else {
+            format!("f{}", self.index)
+        }
+    }
+    fn description(&self) -> String {
+        format!("{}:{}", self.index, self.name)
+    }
+    fn name(&self) -> String {
+        if self.alt {
+            format!("alt-column-{}", self.index)
+        } else {
+            format!("column-{}", self.index)
+        }
+    }
+    fn accepts(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+// Script runs an executable from the bindings directory of the config on the selected items
+// the names are passed as arguments and are also in the environment, see selection_env
+#[derive(Clone)]
+pub struct Script {
+    path: PathBuf,
+    key: String,
+    description: String,
+    accepts: Vec<String>,
+    mutates: bool,
+}
+
+impl Script {
+    pub fn new(
+        path: PathBuf,
+        key: String,
+        description: String,
+        accepts: Vec<String>,
+        mutates: bool,
+    ) -> Self {
+        Script {
+            path,
+            key,
+            description,
+            accepts,
+            mutates,
+        }
+    }
+}
+
+impl Binding for Script {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        let (context, namespace) = origin(ctx);
+        let cmd = selection_env(
+            Exec::cmd(&self.path).args(&ctx.names),
+            ctx,
+            &context,
+            &namespace,
+        );
+        if let Some(cmdline) = dry_run(&cmd) {
+            return Some(cmdline);
+        }
+
+        // a failing script explains itself on stderr, which is shown once the run ends with an error
+        execute_streamed(cmd).map(|output| output.stdout_str())
+    }
+    fn key(&self) -> String {
+        self.key.clone()
+    }
+    fn description(&self) -> String {
+        self.description.clone()
+    }
+    fn accepts(&self) -> Vec<String> {
+        self.accepts.clone()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+    fn mutates(&self) -> bool {
+        self.mutates
+    }
+}
+
+// the selection for a script or --bind command to read, with the context the items are from and
+// the connection flags so its own kubectl calls reach the same cluster as the picker
+// KUBECTL_SELECT_KUBECTL_ARGS is quoted for the shell
+// example: eval kubectl $KUBECTL_SELECT_KUBECTL_ARGS get pods
+fn selection_env(cmd: Exec, ctx: &BindingContext, context: &str, namespace: &str) -> Exec {
+    let kubectl_args: Vec<String> = global_args().iter().map(shell_quote).collect();
+    cmd.env("KUBECTL_SELECT_CONTEXT", context)
+        .env("KUBECTL_SELECT_KUBECTL_ARGS", kubectl_args.join(" "))
+        .env("KUBECTL_SELECT_NAMESPACE", namespace)
+        .env("KUBECTL_SELECT_RESOURCE", &ctx.resource)
+        .env("KUBECTL_SELECT_NAMES", ctx.names.join("\n"))
+}
+
+// the context and namespace the items are from, which are the ones kubectl is pointed at unless
+// listing from several or given with -n
+fn origin(ctx: &BindingContext) -> (String, String) {
+    if let (Some(context), Some(namespace)) = (&ctx.context, &ctx.namespace) {
+        return (context.clone(), namespace.clone());
+    }
+    let (context, namespace) = current_context().unwrap_or_default();
+    (
+        ctx.context.clone().unwrap_or(context),
+        ctx.namespace.clone().unwrap_or(namespace),
+    )
+}
+
+// Template runs a shell command given with --bind, filling in the selection
+// {context}, {resource}, {namespace} and {names} are replaced with the quoted values, names space
+// separated, and the command has the same environment as scripts
+// example: ctrl-t:kubectl delete {resource} {names} -n {namespace} --context {context}
+pub struct Template {
+    key: String,
+    command: String,
+}
+
+impl Template {
+    // parses key:command
+    pub fn parse(bind: &str) -> Result<Self, String> {
+        match bind.split_once(':') {
+            Some((key, command)) if !key.is_empty() && !command.trim().is_empty() => Ok(Template {
+                key: key.to_string(),
+                command: command.to_string(),
+            }),
+            _ => Err(format!("Expected --bind as <key>:<command>, got {}", bind)),
+        }
+    }
+
+    pub fn new(key: String, command: String) -> Self {
+        Template { key, command }
+    }
+
+    fn fill(&self, ctx: &BindingContext, context: &str, namespace: &str) -> String {
+        let names: Vec<String> = ctx.names.iter().map(shell_quote).collect();
+        self.command
+            .replace("{context}", &shell_quote(context))
+            .replace("{resource}", &shell_quote(&ctx.resource))
+            .replace("{namespace}", &shell_quote(namespace))
+            .replace("{names}", &names.join(" "))
+    }
+}
+
+impl Binding for Template {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        let (context, namespace) = origin(ctx);
+        let cmd = selection_env(
+            Exec::shell(self.fill(ctx, &context, &namespace)),
+            ctx,
+            &context,
+            &namespace,
+        );
+        if let Some(cmdline) = dry_run(&cmd) {
+            return Some(cmdline);
+        }
+
+        // when the command fails its stderr is shown once the run ends with an error instead
+        let output = execute_streamed(cmd)?;
+        if output.exit_status.success() {
+            Some(format!("{}{}", output.stdout_str(), output.stderr_str()))
+        } else {
+            Some(output.stdout_str())
+        }
+    }
+    fn key(&self) -> String {
+        self.key.clone()
+    }
+    fn description(&self) -> String {
+        self.command.clone()
+    }
+    fn name(&self) -> String {
+        format!("bind-{}", self.key)
+    }
+    fn accepts(&self) -> Vec<String> {
+        Vec::new()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+    // the command could do anything so it's treated as changing things
+    // which keeps it out of read only mode and in the audit log
+    fn mutates(&self) -> bool {
+        true
+    }
+}
+
+// KubectlPlugin asks which kubectl plugin to run, such as ones installed with krew, on the selected items
+// plugins from the config are the words after kubectl with {resource}, {namespace} and {names}
+// filled in, otherwise the installed plugins are listed and given the names
+// kubectl <plugin> <items..> --namespace <namespace>
+pub struct KubectlPlugin {
+    configured: Vec<String>,
+}
+
+impl KubectlPlugin {
+    pub fn new(configured: Vec<String>) -> Self {
+        KubectlPlugin { configured }
+    }
+
+    // the plugins kubectl can find on the path
+    // kubectl-view_secret is run as kubectl view-secret
+    fn installed() -> Vec<String> {
+        let output = match execute(
+            Exec::cmd("kubectl")
+                .arg("plugin")
+                .arg("list")
+                .arg("--name-only"),
+        ) {
+            Some(output) => output.stdout_str(),
+            None => return Vec::new(),
+        };
+
+        output
+            .lines()
+            .filter_map(|l| l.rsplit('/').next())
+            .filter_map(|l| l.strip_prefix("kubectl-"))
+            .map(|l| l.replace('_', "-"))
+            .collect()
+    }
+}
+
+impl Binding for KubectlPlugin {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        let choices = if self.configured.is_empty() {
+            KubectlPlugin::installed()
+        } else {
+            self.configured.clone()
+        };
+        if choices.is_empty() {
+            return Some("No kubectl plugins found, install some with krew\n".into());
+        }
+        let chosen = picker::pick_one("plugin", &choices)?;
+
+        let mut words = chosen.split_whitespace();
+        let plugin = words.next()?;
+        let mut args = Vec::new();
+        if chosen.contains('{') {
+            for word in words {
+                if word == "{names}" {
+                    args.extend(ctx.names.iter().cloned());
+                } else {
+                    args.push(
+                        word.replace("{resource}", &ctx.resource)
+                            .replace("{namespace}", ctx.namespace.as_deref().unwrap_or_default()),
+                    );
+                }
+            }
+        } else {
+            args.extend(words.map(String::from));
+            args.extend(ctx.names.iter().cloned());
+        }
+
+        let cmd = kubectl_base_cmd(ctx.namespace.as_deref(), plugin, None).args(&args);
+        if let Some(cmdline) = dry_run(&cmd) {
+            return Some(cmdline);
+        }
+        execute_streamed(cmd).map(|output| output.stdout_str())
+    }
+    fn key(&self) -> String {
+        "alt-k".into()
+    }
+    fn description(&self) -> String {
+        "Kubectl Plugin".into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        Vec::new()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+    // plugins could do anything so they're treated as changing things
+    fn mutates(&self) -> bool {
+        true
+    }
+    fn kubectl_only(&self) -> bool {
+        true
+    }
+}
+
+// Helm runs a helm command on the selected helm releases, in each release's own namespace
+// helm <command> <release> --namespace <namespace>
+pub struct Helm {
+    command: &'static str,
+    key: &'static str,
+    description: &'static str,
+}
+
+impl Helm {
+    pub fn status() -> Self {
+        Helm {
+            command: "status",
+            key: "alt-s",
+            description: "Helm Status",
+        }
+    }
+    pub fn values() -> Self {
+        Helm {
+            command: "get values",
+            key: "alt-l",
+            description: "Helm Values",
+        }
+    }
+    pub fn history() -> Self {
+        Helm {
+            command: "history",
+            key: "alt-h",
+            description: "Helm History",
+        }
+    }
+    // rolls back to the previous revision
+    pub fn rollback() -> Self {
+        Helm {
+            command: "rollback",
+            key: "alt-b",
+            description: "Helm Rollback",
+        }
+    }
+}
+
+impl Binding for Helm {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        // helm list puts the namespace of each release in the second column
+        let mut output = String::new();
+        for columns in &ctx.columns {
+            let (release, namespace) = match (columns.get(0), columns.get(1)) {
+                (Some(release), Some(namespace)) => (release, namespace),
+                _ => continue,
+            };
+            let cmd = Exec::cmd("helm")
+                .args(&self.command.split(' ').collect::<Vec<_>>())
+                .arg(release)
+                .arg("--namespace")
+                .arg(namespace)
+                .args(&helm_args());
+            output.push_str(&stream(cmd)?);
+        }
+        Some(output)
+    }
+    fn key(&self) -> String {
+        self.key.into()
+    }
+    fn description(&self) -> String {
+        self.description.into()
+    }
+    fn accepts(&self) -> Vec<String> {
+        BindingContext::accepts_helm()
+    }
+    fn mutates(&self) -> bool {
+        self.command == "rollback"
+    }
+}
+
+// Overridden runs a shell command from the config in place of another binding
+// it keeps the binding's key, name and the resources it works for
+pub struct Overridden {
+    inner: Arc<dyn Binding + Send + Sync>,
+    template: Template,
+}
+
+impl Overridden {
+    pub fn new(inner: Arc<dyn Binding + Send + Sync>, command: String) -> Self {
+        let template = Template::new(inner.key(), command);
+        Overridden { inner, template }
+    }
+}
+
+impl Binding for Overridden {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        self.template.run(ctx)
+    }
+    fn key(&self) -> String {
+        self.inner.key()
+    }
+    fn description(&self) -> String {
+        self.inner.description()
+    }
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+    fn accepts(&self) -> Vec<String> {
+        self.inner.accepts()
+    }
+    fn needs_columns(&self) -> bool {
+        false
+    }
+    // a shell command in place of even a read only binding could do anything, so like --bind
+    // it's kept out of read only mode and in the audit log
+    fn mutates(&self) -> bool {
+        true
+    }
+    fn max_items(&self) -> Option<usize> {
+        self.inner.max_items()
+    }
+    fn requires_confirmation(&self) -> Confirm {
+        self.inner.requires_confirmation()
+    }
+    fn warning(&self) -> Option<String> {
+        self.inner.warning()
+    }
+    fn describe_items(&self, ctx: &BindingContext) -> Option<Vec<String>> {
+        self.inner.describe_items(ctx)
+    }
+    fn runs_for(&self, resource: &str) -> bool {
+        self.inner.runs_for(resource)
+    }
+}
+
+// Rebound moves another binding to a different key, such as one remapped in the config
+pub struct Rebound {
+    inner: Arc<dyn Binding + Send + Sync>,
+    key: String,
+}
+
+impl Rebound {
+    pub fn new(inner: Arc<dyn Binding + Send + Sync>, key: String) -> Self {
+        Rebound { inner, key }
+    }
+}
+
+impl Binding for Rebound {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        self.inner.run(ctx)
+    }
+    fn key(&self) -> String {
+        self.key.clone()
+    }
+    fn description(&self) -> String {
+        self.inner.description()
+    }
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+    fn accepts(&self) -> Vec<String> {
+        self.inner.accepts()
+    }
+    fn needs_columns(&self) -> bool {
+        self.inner.needs_columns()
+    }
+    fn structured_output(&self) -> bool {
+        self.inner.structured_output()
+    }
+    fn mutates(&self) -> bool {
+        self.inner.mutates()
+    }
+    fn max_items(&self) -> Option<usize> {
+        self.inner.max_items()
+    }
+    fn requires_confirmation(&self) -> Confirm {
+        self.inner.requires_confirmation()
+    }
+    fn warning(&self) -> Option<String> {
+        self.inner.warning()
+    }
+    fn describe_items(&self, ctx: &BindingContext) -> Option<Vec<String>> {
+        self.inner.describe_items(ctx)
+    }
+    fn batches(&self) -> bool {
+        self.inner.batches()
+    }
+    fn navigate(&self, ctx: &BindingContext) -> Option<Navigation> {
+        self.inner.navigate(ctx)
+    }
+    fn runs_for(&self, resource: &str) -> bool {
+        self.inner.runs_for(resource)
+    }
+}
+
+// follows several log streams at once, interleaving their lines as they arrive
+fn follow_interleaved(cmds: Vec<Exec>, filter: Option<&Regex>) {
+    let mut children = Vec::new();
+    let mut readers = Vec::new();
+    for cmd in cmds {
+        throttle();
+        let mut child = match supervisor::spawn(cmd.stdout(Redirection::Pipe)) {
+            Some(child) => child,
+            None => continue,
+        };
+        if let Some(stdout) = child.stdout.take() {
+            let filter = filter.cloned();
+            readers.push(thread::spawn(move || {
+                for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                    if let Some(line) = format_log_line(&line, filter.as_ref()) {
+                        println!("{}", line);
+                    }
+                }
+            }));
+        }
+        children.push(child);
+    }
+
+    supervisor::follow_all(children);
+    for reader in readers {
+        let _ = reader.join();
+    }
+}
+
+// drops lines that don't match the filter and highlights the matches in those that do
+// kubectl prefixes lines with [pod/<pod>/<container>] when following several pods, which is
+// colored by picking from the prefix so each container keeps the same color
+fn format_log_line(line: &str, filter: Option<&Regex>) -> Option<String> {
+    let split = match line.find("] ") {
+        Some(end) if line.starts_with('[') => end + 1,
+        _ => 0,
+    };
+    let (prefix, message) = line.split_at(split);
+
+    if let Some(filter) = filter {
+        if !filter.is_match(message) {
+            return None;
+        }
+    }
+    if !theme::colored() {
+        return Some(line.to_string());
+    }
+
+    let prefix = if prefix.is_empty() {
+        String::new()
+    } else {
+        let color = 31 + prefix.bytes().map(usize::from).sum::<usize>() % 6;
+        format!("\x1b[{}m{}\x1b[0m", color, prefix)
+    };
+    let message = match filter {
+        Some(filter) => filter
+            .replace_all(message, "\x1b[1;31m$0\x1b[0m")
+            .into_owned(),
+        None => message.to_string(),
+    };
+    Some(format!("{}{}", prefix, message))
+}
+
+// lines up tab separated rows into a table
+fn tab_align(rows: &[String]) -> Option<String> {
+    let mut tab_writer = TabWriter::new(vec![]);
+    for row in rows {
+        writeln!(tab_writer, "{}", row).ok()?;
+    }
+    tab_writer.flush().ok()?;
+
+    String::from_utf8(tab_writer.into_inner().ok()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use subprocess::ExitStatus;
+
+    fn context() -> BindingContext {
+        BindingContext {
+            context: None,
+            namespace: Some("default".into()),
+            resource: "pods".into(),
+            names: vec!["api-0".into(), "api-1".into()],
+            columns: vec![
+                vec!["api-0".into(), "node-a".into()],
+                vec!["api-1".into(), "node-b".into()],
+            ],
+            header: vec!["NAME".into(), "NOMINATED NODE".into()],
+        }
+    }
+
+    fn failed(status: ExitStatus, stderr: &str) -> CaptureData {
+        CaptureData {
+            stdout: Vec::new(),
+            stderr: stderr.as_bytes().to_vec(),
+            exit_status: status,
+        }
+    }
+
+    #[test]
+    fn field_name_lowercases_and_replaces_the_rest() {
+        assert_eq!(field_name("NOMINATED NODE"), "nominated_node");
+        assert_eq!(field_name("LAST-SEEN"), "last_seen");
+        assert_eq!(field_name("IP"), "ip");
+    }
+
+    #[test]
+    fn to_template_fills_in_fields_for_each_item() {
+        let names = Names::template(
+            "\n".into(),
+            "{{.name}} on {{ .nominated_node }} in {{.namespace}}".into(),
+        );
+        assert_eq!(
+            names.run(&context()).unwrap(),
+            "api-0 on node-a in default\napi-1 on node-b in default"
+        );
+    }
+
+    #[test]
+    fn to_template_marks_missing_fields() {
+        let names = Names::template(", ".into(), "{{.name}}={{.ip}}".into());
+        assert_eq!(
+            names.run(&context()).unwrap(),
+            "api-0=<no value>, api-1=<no value>"
+        );
+    }
+
+    #[test]
+    fn template_fills_in_the_selection() {
+        let template = Template::parse(
+            "ctrl-t:kubectl delete {resource} {names} -n {namespace} --context {context}",
+        )
+        .unwrap();
+        assert_eq!(
+            template.fill(&context(), "prod", "default"),
+            "kubectl delete pods api-0 api-1 -n default --context prod"
+        );
+        // an unknown namespace is still an argument rather than nothing at all
+        assert_eq!(
+            template.fill(&context(), "prod", ""),
+            "kubectl delete pods api-0 api-1 -n '' --context prod"
+        );
+    }
+
+    #[test]
+    fn refuses_several_names_from_usage_errors() {
+        assert!(refuses_several_names(&failed(
+            ExitStatus::Exited(1),
+            "error: USAGE: cordon NODE [flags]\n"
+        )));
+        assert!(refuses_several_names(&failed(
+            ExitStatus::Exited(1),
+            "error: exactly one NAME is required, got 2\n"
+        )));
+    }
+
+    #[test]
+    fn refuses_several_names_not_from_other_failures() {
+        assert!(!refuses_several_names(&failed(
+            ExitStatus::Exited(1),
+            "Error from server (NotFound): pods \"api-2\" not found\n"
+        )));
+        assert!(!refuses_several_names(&failed(
+            ExitStatus::Exited(0),
+            "USAGE: cordon NODE [flags]\n"
+        )));
+    }
+}
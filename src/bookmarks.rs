@@ -0,0 +1,90 @@
+use crate::bindings::Navigation;
+use crate::config::config_dir;
+use crate::picker;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+// listings saved to jump back to, kept in the config directory as a json line each in the order
+// they were added
+
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
+pub struct Bookmark {
+    pub context: Option<String>,
+    pub namespace: Option<String>,
+    pub resource: String,
+    pub names: Vec<String>,
+    pub selector: Option<String>,
+    pub query: Vec<String>,
+}
+
+impl Bookmark {
+    // the listing with the query it was bookmarked with
+    pub fn navigation(&self) -> Navigation {
+        Navigation {
+            resource: self.resource.clone(),
+            namespace: self.namespace.clone(),
+            names: self.names.clone(),
+            selector: self.selector.clone(),
+            query: self.query.clone(),
+            ..Default::default()
+        }
+    }
+
+    // the listing the way kubectl would be given it, then the query
+    // example: deployments api web -n prod --context prod 'ready'
+    fn label(&self) -> String {
+        let mut label = format!("{} {}", self.resource, self.names.join(" "));
+        if let Some(namespace) = &self.namespace {
+            label.push_str(&format!(" -n {}", namespace));
+        }
+        if let Some(selector) = &self.selector {
+            label.push_str(&format!(" -l {}", selector));
+        }
+        if let Some(context) = &self.context {
+            label.push_str(&format!(" --context {}", context));
+        }
+        if !self.query.is_empty() {
+            label.push_str(&format!(" '{}'", self.query.join(" ")));
+        }
+        label
+    }
+}
+
+pub fn load() -> Vec<Bookmark> {
+    let path = match config_dir() {
+        Some(dir) => dir.join("bookmarks"),
+        None => return Vec::new(),
+    };
+    let contents = fs::read_to_string(path).unwrap_or_default();
+    contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+// adds a bookmark unless the same one is already there
+pub fn add(bookmark: Bookmark) -> Result<(), String> {
+    let mut bookmarks = load();
+    if bookmarks.contains(&bookmark) {
+        return Ok(());
+    }
+    bookmarks.push(bookmark);
+
+    let dir = config_dir().ok_or("Failed to save the bookmark: no config directory")?;
+    let lines: Vec<String> = bookmarks
+        .iter()
+        .filter_map(|b| serde_json::to_string(b).ok())
+        .collect();
+    let path = dir.join("bookmarks");
+    fs::create_dir_all(&dir)
+        .and_then(|_| fs::write(&path, lines.join("\n") + "\n"))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+// asks which of the bookmarks to jump to
+pub fn pick(bookmarks: &[Bookmark]) -> Option<Bookmark> {
+    let labels: Vec<String> = bookmarks.iter().map(Bookmark::label).collect();
+    let chosen = picker::pick_one("bookmark", &labels)?;
+    let index = labels.iter().position(|l| *l == chosen)?;
+    bookmarks.get(index).cloned()
+}
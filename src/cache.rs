@@ -0,0 +1,83 @@
+use crate::config::cache_dir;
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+// output of listings kept on disk for a short while with --cache-ttl so running again straight away
+// is instant
+// entries are keyed by the whole kubectl command line, which has the context, namespace and resource,
+// along with when the kubeconfig last changed so switching context doesn't reuse another cluster's rows
+
+// how long entries are used for, or None when caching is off as it is by default
+static TTL: Mutex<Option<Duration>> = Mutex::new(None);
+
+pub fn set_ttl(ttl: Option<Duration>) {
+    *TTL.lock().unwrap() = ttl;
+}
+
+// the cached output of a command if it's younger than the ttl
+pub fn get(cmdline: &str) -> Option<String> {
+    let ttl = (*TTL.lock().unwrap())?;
+    let path = entry(cmdline)?;
+    let age = fs::metadata(&path).ok()?.modified().ok()?.elapsed().ok()?;
+    if age > ttl {
+        return None;
+    }
+    fs::read_to_string(path).ok()
+}
+
+// keeps the output of a command for the next run
+// failing to write just means the next run asks the cluster again
+pub fn put(cmdline: &str, output: &str) {
+    if TTL.lock().unwrap().is_none() {
+        return;
+    }
+    if let (Some(dir), Some(path)) = (dir(), entry(cmdline)) {
+        let _ = fs::create_dir_all(dir).and_then(|_| fs::write(path, output));
+    }
+}
+
+// removes everything cached, such as after a binding changed things
+pub fn clear() -> Result<(), String> {
+    let dir = match dir() {
+        Some(dir) => dir,
+        None => return Ok(()),
+    };
+    match fs::remove_dir_all(&dir) {
+        Err(e) if e.kind() != ErrorKind::NotFound => {
+            Err(format!("Failed to clear {}: {}", dir.display(), e))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn dir() -> Option<PathBuf> {
+    cache_dir()
+}
+
+fn entry(cmdline: &str) -> Option<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    cmdline.hash(&mut hasher);
+    for kubeconfig in kubeconfigs() {
+        if let Ok(modified) = fs::metadata(&kubeconfig).and_then(|m| m.modified()) {
+            modified.hash(&mut hasher);
+        }
+    }
+    Some(dir()?.join(format!("{:016x}", hasher.finish())))
+}
+
+// the kubeconfig files kubectl reads the current context from
+fn kubeconfigs() -> Vec<PathBuf> {
+    match env::var_os("KUBECONFIG") {
+        Some(paths) if !paths.is_empty() => env::split_paths(&paths).collect(),
+        _ => env::var_os("HOME")
+            .map(|home| PathBuf::from(home).join(".kube").join("config"))
+            .into_iter()
+            .collect(),
+    }
+}
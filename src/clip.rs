@@ -0,0 +1,123 @@
+use crate::supervisor;
+use clipboard::{ClipboardContext, ClipboardProvider};
+use std::env;
+use std::fs::OpenOptions;
+use std::io::Write;
+use subprocess::{Exec, ExitStatus};
+
+// the ways of setting the clipboard, tried in order until one works
+// the clipboard commands come before the native clipboard since on X11 the native one
+// only holds the contents while we are running
+#[derive(Clone, Copy)]
+enum Backend {
+    WlCopy,
+    Xclip,
+    Xsel,
+    Pbcopy,
+    ClipExe,
+    Native,
+    Osc52,
+}
+
+const BACKENDS: [Backend; 7] = [
+    Backend::WlCopy,
+    Backend::Xclip,
+    Backend::Xsel,
+    Backend::Pbcopy,
+    Backend::ClipExe,
+    Backend::Native,
+    Backend::Osc52,
+];
+
+impl Backend {
+    fn name(self) -> &'static str {
+        match self {
+            Backend::WlCopy => "wl-copy",
+            Backend::Xclip => "xclip",
+            Backend::Xsel => "xsel",
+            Backend::Pbcopy => "pbcopy",
+            Backend::ClipExe => "clip.exe",
+            Backend::Native => "native clipboard",
+            Backend::Osc52 => "osc52",
+        }
+    }
+
+    // skip display server specific backends outside of that display server
+    fn available(self) -> bool {
+        match self {
+            Backend::WlCopy => has_env("WAYLAND_DISPLAY"),
+            Backend::Xclip | Backend::Xsel => has_env("DISPLAY"),
+            Backend::Pbcopy => cfg!(target_os = "macos"),
+            Backend::ClipExe => has_env("WSL_DISTRO_NAME") || cfg!(windows),
+            Backend::Native | Backend::Osc52 => true,
+        }
+    }
+
+    fn copy(self, text: &str) -> bool {
+        match self {
+            Backend::WlCopy => run(Exec::cmd("wl-copy"), text),
+            Backend::Xclip => run(Exec::cmd("xclip").arg("-selection").arg("clipboard"), text),
+            Backend::Xsel => run(Exec::cmd("xsel").arg("--clipboard").arg("--input"), text),
+            Backend::Pbcopy => run(Exec::cmd("pbcopy"), text),
+            Backend::ClipExe => run(Exec::cmd("clip.exe"), text),
+            Backend::Native => ClipboardContext::new()
+                .and_then(|mut ctx| ctx.set_contents(text.to_string()))
+                .is_ok(),
+            Backend::Osc52 => osc52(text),
+        }
+    }
+}
+
+// copies text to the clipboard with the first backend that works and returns its name
+// if none work the error lists the ones that were tried
+pub fn copy(text: &str) -> Result<&'static str, String> {
+    let mut tried = Vec::new();
+    for backend in BACKENDS.iter().copied().filter(|b| b.available()) {
+        if backend.copy(text) {
+            return Ok(backend.name());
+        }
+        tried.push(backend.name());
+    }
+    Err(format!(
+        "Failed to copy to the clipboard, tried {}",
+        tried.join(", ")
+    ))
+}
+
+// copies text and reports which backend took it on stderr
+// stdout only gets the text itself when no clipboard works, so it isn't lost
+pub fn copy_output(text: &str, what: &str) -> Option<String> {
+    match copy(text) {
+        Ok(backend) => {
+            eprintln!("Copied {} to the clipboard with {}", what, backend);
+            None
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            Some(text.to_string())
+        }
+    }
+}
+
+fn has_env(var: &str) -> bool {
+    env::var_os(var).map_or(false, |v| !v.is_empty())
+}
+
+fn run(cmd: Exec, text: &str) -> bool {
+    matches!(supervisor::feed(cmd, text), Some(ExitStatus::Exited(0)))
+}
+
+// asks the terminal itself to set the clipboard, which works over ssh
+// there's no way to tell whether the terminal supports it so this always counts as working
+fn osc52(text: &str) -> bool {
+    let mut sequence = format!("\x1b]52;c;{}\x07", base64::encode(text));
+    // tmux swallows escape sequences unless they're wrapped in its passthrough
+    if has_env("TMUX") {
+        sequence = format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"));
+    }
+
+    match OpenOptions::new().write(true).open("/dev/tty") {
+        Ok(mut tty) => tty.write_all(sequence.as_bytes()).is_ok(),
+        Err(_) => false,
+    }
+}
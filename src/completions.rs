@@ -0,0 +1,156 @@
+use crate::kubectl::{capture, kubectl_base_cmd};
+use clap::{App, ArgSettings};
+use clap_generate::generators::{Bash, Fish, Zsh};
+use clap_generate::{generate, Generator};
+use std::io;
+
+const BIN_NAME: &str = "kubectl-select";
+
+// prints the completion script for a shell
+// clap's static script covers the flags, and a wrapper on top of it asks the tool itself
+// for resource types and namespaces from the cluster when those are being completed
+pub fn print(app: &mut App, shell: &str) {
+    match shell {
+        "bash" => print_with::<Bash>(app, bash_dynamic),
+        "zsh" => print_with::<Zsh>(app, zsh_dynamic),
+        "fish" => print_with::<Fish>(app, fish_dynamic),
+        _ => unreachable!("clap only allows known shells"),
+    }
+}
+
+fn print_with<G: Generator>(app: &mut App, dynamic: fn(&str) -> String) {
+    generate::<G, _>(app, BIN_NAME, &mut io::stdout());
+    print!("{}", dynamic(&value_flags(app).join(" ")));
+}
+
+// the candidates for a dynamic completion, one per line
+// resources are the plain names from api-resources, so deployments.apps completes as deployments
+pub fn candidates(kind: &str) -> Vec<String> {
+    let mut candidates: Vec<String> = match kind {
+        "resources" => capture(
+            kubectl_base_cmd(None, "api-resources", None)
+                .arg("--output")
+                .arg("name"),
+        )
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|l| l.split('.').next())
+        .map(String::from)
+        .collect(),
+        "namespaces" => capture(
+            kubectl_base_cmd(None, "get", "namespaces".to_string())
+                .arg("--output")
+                .arg("name"),
+        )
+        .unwrap_or_default()
+        .lines()
+        .map(|l| l.trim_start_matches("namespace/").to_string())
+        .collect(),
+        _ => Vec::new(),
+    };
+    candidates.sort();
+    candidates.dedup();
+    candidates
+}
+
+// flags that take a value, so the word after them isn't mistaken for the resource
+fn value_flags(app: &App) -> Vec<String> {
+    let mut flags = Vec::new();
+    for arg in app.get_arguments() {
+        if !arg.is_set(ArgSettings::TakesValue) {
+            continue;
+        }
+        if let Some(short) = arg.get_short() {
+            flags.push(format!("-{}", short));
+        }
+        if let Some(long) = arg.get_long() {
+            flags.push(format!("--{}", long));
+        }
+    }
+    flags
+}
+
+fn bash_dynamic(value_flags: &str) -> String {
+    format!(
+        r#"
+_kubectl_select_dynamic() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}" prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    case "$prev" in
+        -n|--namespace)
+            COMPREPLY=($(compgen -W "$(kubectl-select __complete namespaces 2>/dev/null)" -- "$cur"))
+            return ;;
+    esac
+    if [[ "$cur" != -* ]]; then
+        local i positional=0
+        for ((i = 1; i < COMP_CWORD; i++)); do
+            case " {flags} " in
+                *" ${{COMP_WORDS[i]}} "*) ((i++)) ;;
+                *) [[ "${{COMP_WORDS[i]}}" != -* ]] && positional=1 ;;
+            esac
+        done
+        if [[ $positional -eq 0 ]]; then
+            COMPREPLY=($(compgen -W "$(kubectl-select __complete resources 2>/dev/null)" -- "$cur"))
+            return
+        fi
+    fi
+    _kubectl-select "$@"
+}}
+complete -F _kubectl_select_dynamic -o bashdefault -o default kubectl-select
+"#,
+        flags = value_flags
+    )
+}
+
+fn zsh_dynamic(value_flags: &str) -> String {
+    format!(
+        r#"
+_kubectl_select_dynamic() {{
+    if [[ ${{words[CURRENT-1]}} == (-n|--namespace) ]]; then
+        compadd -- ${{(f)"$(kubectl-select __complete namespaces 2>/dev/null)"}}
+        return
+    fi
+    if [[ ${{words[CURRENT]}} != -* ]]; then
+        local i positional=0
+        local -a value_flags=({flags})
+        for ((i = 2; i < CURRENT; i++)); do
+            if (( ${{value_flags[(Ie)${{words[i]}}]}} )); then
+                ((i++))
+            elif [[ ${{words[i]}} != -* ]]; then
+                positional=1
+            fi
+        done
+        if (( ! positional )); then
+            compadd -- ${{(f)"$(kubectl-select __complete resources 2>/dev/null)"}}
+            return
+        fi
+    fi
+    _kubectl-select "$@"
+}}
+compdef _kubectl_select_dynamic kubectl-select
+"#,
+        flags = value_flags
+    )
+}
+
+fn fish_dynamic(value_flags: &str) -> String {
+    format!(
+        r#"
+function __kubectl_select_needs_resource
+    set -l skip 0
+    for arg in (commandline -opc)[2..-1]
+        if test $skip -eq 1
+            set skip 0
+        else if contains -- $arg {flags}
+            set skip 1
+        else if not string match -q -- '-*' $arg
+            return 1
+        end
+    end
+    return 0
+end
+complete -c kubectl-select -n __kubectl_select_needs_resource -f -a '(kubectl-select __complete resources 2>/dev/null)'
+complete -c kubectl-select -s n -l namespace -x -a '(kubectl-select __complete namespaces 2>/dev/null)'
+"#,
+        flags = value_flags
+    )
+}
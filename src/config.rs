@@ -0,0 +1,204 @@
+use crate::theme::ThemeConfig;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+// settings read from $XDG_CONFIG_HOME/kubectl-select/config.toml, falling back to ~/.config
+// every setting is optional and a missing file is the same as an empty one
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    // file to append a record of every kubectl command run by a mutating binding to
+    pub audit_log: Option<PathBuf>,
+
+    // binding names mapped to the key they should use instead, or an empty key to disable them
+    // [keys]
+    // copy = "alt-c"
+    // cordon = ""
+    pub keys: HashMap<String, String>,
+
+    // binding names mapped to whether to ask before running them, over what the binding does itself
+    // typed asks for yes in full rather than y
+    // [confirm]
+    // cordon = "never"
+    // edit = "ask"
+    // evict = "typed"
+    pub confirm: HashMap<String, Confirm>,
+
+    // bindings to leave out by name, or mutating for every binding that changes things
+    // disabled = ["cordon", "edit"]
+    pub disabled: Vec<String>,
+
+    // shell commands that replace what a binding runs, keeping its key and the resources it works for
+    // {context}, {resource}, {namespace} and {names} are filled in the same as --bind
+    // [overrides]
+    // describe = "kubectl describe {resource} {names} --namespace {namespace} --show-events=false"
+    pub overrides: HashMap<String, String>,
+
+    pub theme: ThemeConfig,
+
+    // default for --preview-window
+    pub preview_window: Option<String>,
+
+    // kubectl plugins offered by the plugin binding, instead of every installed one
+    // kubectl-plugins = ["neat get {resource} {names}", "view-secret"]
+    pub kubectl_plugins: Vec<String>,
+
+    // custom columns to list a resource with, by the resource name as given on the command line
+    // [columns]
+    // pods = "NODE:.spec.nodeName,IP:.status.podIP"
+    pub columns: HashMap<String, String>,
+
+    // flags a resource is listed with unless they're given on the command line, by any of its names
+    // [resources.pods]
+    // wide = true
+    // [resources.events]
+    // sort-by = ".lastTimestamp"
+    pub resources: HashMap<String, ResourceDefaults>,
+
+    // restart counts above which the RESTARTS column is colored yellow, then red
+    // [restarts]
+    // warning = 0
+    // critical = 10
+    pub restarts: Restarts,
+
+    // settings bundled under a name and used with --profile or $KUBECTL_SELECT_PROFILE
+    // [profiles.prod]
+    // context = "prod"
+    // namespace = "api"
+    // resource = "deployments"
+    // keys = { delete = "" }
+    // theme = { preset = "solarized" }
+    pub profiles: HashMap<String, Profile>,
+
+    // default for --proxy
+    pub proxy: bool,
+
+    // default for --rate-limit
+    pub rate_limit: Option<u32>,
+
+    // default for --max-select
+    pub max_select: Option<usize>,
+
+    // default for --frecent
+    pub frecent: bool,
+
+    // default for --fit
+    pub fit: bool,
+
+    // default for --from-json
+    pub from_json: bool,
+
+    // defaults for --height and --full-screen
+    pub height: Option<String>,
+    pub full_screen: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Profile {
+    pub context: Option<String>,
+    pub namespace: Option<String>,
+    pub resource: Option<String>,
+    pub keys: HashMap<String, String>,
+    pub disabled: Vec<String>,
+    pub theme: Option<ThemeConfig>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ResourceDefaults {
+    pub wide: bool,
+    pub sort_by: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Confirm {
+    Never,
+    Ask,
+    Typed,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(default, deny_unknown_fields)]
+pub struct Restarts {
+    pub warning: u64,
+    pub critical: u64,
+}
+
+impl Default for Restarts {
+    fn default() -> Self {
+        Restarts {
+            warning: 0,
+            critical: 10,
+        }
+    }
+}
+
+impl Config {
+    // reads the config file
+    // a file that exists but can't be read or parsed is an error rather than silently ignored
+    pub fn load() -> Result<Config, String> {
+        let path = match config_dir() {
+            Some(dir) => dir.join("config.toml"),
+            None => return Ok(Config::default()),
+        };
+
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Config::default()),
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+
+        let mut config: Config = toml::from_str(&contents)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        config.audit_log = config.audit_log.map(expand_home);
+
+        Ok(config)
+    }
+
+    // takes the named profile out of the config, with its keys and theme merged into the rest
+    // the context, namespace and resource are left to the caller since the command line wins over them
+    pub fn take_profile(&mut self, name: &str) -> Result<Profile, String> {
+        let mut profile = self
+            .profiles
+            .remove(name)
+            .ok_or_else(|| format!("No profile named {} in the config", name))?;
+        self.keys.extend(profile.keys.drain());
+        self.disabled.append(&mut profile.disabled);
+        if let Some(theme) = profile.theme.take() {
+            self.theme = theme;
+        }
+        Ok(profile)
+    }
+}
+
+// directory the config and any other state of the tool lives in
+pub fn config_dir() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("kubectl-select"))
+}
+
+// directory for what can be thrown away, such as cached listings
+pub fn cache_dir() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_CACHE_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".cache"),
+    };
+    Some(base.join("kubectl-select"))
+}
+
+// expands a leading ~ to the home directory since toml has no notion of it
+fn expand_home(path: PathBuf) -> PathBuf {
+    match (path.strip_prefix("~"), env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => path,
+    }
+}
@@ -0,0 +1,158 @@
+use crate::config::config_dir;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::debug;
+
+// how often and how lately each item had a binding run on it, so the usual ones can be listed first
+// kept in the config directory as a json list
+
+// the least used items are forgotten past this many
+const MAX_ENTRIES: usize = 1000;
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    context: Option<String>,
+    namespace: Option<String>,
+    resource: String,
+    name: String,
+    count: u64,
+    // seconds since the epoch
+    last: u64,
+}
+
+impl Entry {
+    fn is_for(&self, context: Option<&str>, namespace: Option<&str>, resource: &str) -> bool {
+        self.context.as_deref() == context
+            && self.namespace.as_deref() == namespace
+            && self.resource == resource
+    }
+
+    // the count weighted by how lately it was chosen, the way zoxide ranks directories
+    // example: chosen 3 times, last an hour ago -> 6
+    fn score(&self, now: u64) -> f64 {
+        let age = now.saturating_sub(self.last);
+        let weight = if age < 60 * 60 {
+            4.0
+        } else if age < 24 * 60 * 60 {
+            2.0
+        } else if age < 7 * 24 * 60 * 60 {
+            0.5
+        } else {
+            0.25
+        };
+        self.count as f64 * weight
+    }
+}
+
+// counts the names as chosen once more
+// failing to write only loses the ranking so it isn't an error
+pub fn record(context: Option<&str>, namespace: Option<&str>, resource: &str, names: &[String]) {
+    let path = match path() {
+        Some(path) => path,
+        None => return,
+    };
+    let now = now();
+
+    let mut entries = load();
+    for name in names {
+        match entries
+            .iter_mut()
+            .find(|e| e.is_for(context, namespace, resource) && e.name == *name)
+        {
+            Some(entry) => {
+                entry.count += 1;
+                entry.last = now;
+            }
+            None => entries.push(Entry {
+                context: context.map(String::from),
+                namespace: namespace.map(String::from),
+                resource: resource.to_string(),
+                name: name.clone(),
+                count: 1,
+                last: now,
+            }),
+        }
+    }
+    if entries.len() > MAX_ENTRIES {
+        entries.sort_by(|a, b| b.score(now).partial_cmp(&a.score(now)).unwrap());
+        entries.truncate(MAX_ENTRIES);
+    }
+
+    let written = serde_json::to_string(&entries)
+        .map_err(|e| e.to_string())
+        .and_then(|json| {
+            fs::create_dir_all(path.parent().unwrap_or(&path))
+                .and_then(|_| fs::write(&path, json))
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = written {
+        debug!(error = %e, "failed to write the frecency");
+    }
+}
+
+// the score of every name chosen before in a listing, higher for the ones to list first
+pub fn scores(
+    context: Option<&str>,
+    namespace: Option<&str>,
+    resource: &str,
+) -> HashMap<String, f64> {
+    let now = now();
+    load()
+        .into_iter()
+        .filter(|e| e.is_for(context, namespace, resource))
+        .map(|e| {
+            let score = e.score(now);
+            (e.name, score)
+        })
+        .collect()
+}
+
+fn load() -> Vec<Entry> {
+    path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn path() -> Option<PathBuf> {
+    Some(config_dir()?.join("frecency"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chosen(count: u64, last: u64) -> Entry {
+        Entry {
+            context: None,
+            namespace: Some("default".into()),
+            resource: "pods".into(),
+            name: "api-0".into(),
+            count,
+            last,
+        }
+    }
+
+    #[test]
+    fn score_weights_the_count_by_how_lately() {
+        let now = 100_000_000;
+        assert_eq!(chosen(3, now - 60).score(now), 12.0);
+        assert_eq!(chosen(3, now - 60 * 60).score(now), 6.0);
+        assert_eq!(chosen(3, now - 2 * 24 * 60 * 60).score(now), 1.5);
+        assert_eq!(chosen(3, now - 30 * 24 * 60 * 60).score(now), 0.75);
+    }
+
+    #[test]
+    fn score_counts_a_clock_gone_backwards_as_just_now() {
+        assert_eq!(chosen(1, 2_000).score(1_000), 4.0);
+    }
+}
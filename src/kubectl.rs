@@ -1,13 +1,190 @@
-use crate::bindings::Binding;
+use crate::age::Ages;
+use crate::audit;
+use crate::bindings::{Binding, BindingContext};
+use crate::cache;
+use crate::config::Restarts;
+use crate::progress;
+use crate::resources;
+use crate::supervisor;
+use crate::theme;
+use serde_json::{json, Value};
 use skim::prelude::*;
 use std::collections::HashMap;
+use std::ffi::OsStr;
 use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use subprocess::Exec;
+use std::time::{Duration, Instant};
+use subprocess::{CaptureData, Exec, ExitStatus};
 use tabwriter::TabWriter;
+use tracing::{debug, info, warn};
 
 type BindingMap = HashMap<String, Arc<dyn Binding + Sync + Send>>;
 
+// keys bound straight to skim actions rather than to bindings
+// description, key, skim action
+pub const PICKER_KEYS: [(&str, &str, &str); 4] = [
+    ("Toggle Preview", "ctrl-p", "toggle-preview"),
+    ("Select All", "ctrl-a", "select-all"),
+    ("Deselect All", "alt-a", "deselect-all"),
+    ("Invert Selection", "alt-t", "toggle-all"),
+];
+
+// skim's own keys that are left as they are, for the help page
+// description, keys
+pub const SKIM_KEYS: [(&str, &str); 5] = [
+    ("Move", "up / down"),
+    ("Scroll", "pgup / pgdn"),
+    ("Select And Move", "tab / btab"),
+    ("Accept", "enter"),
+    ("Abort", "esc / ctrl-c"),
+];
+
+// flags appended to every kubectl command the tool spawns
+// set once at startup so bindings don't need to know about connection options
+static GLOBAL_ARGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+pub fn set_global_args(args: Vec<String>) {
+    *GLOBAL_ARGS.lock().unwrap() = args;
+}
+
+// the connection flags for commands the tool doesn't build itself, like scripts, to pass on to kubectl
+pub fn global_args() -> Vec<String> {
+    GLOBAL_ARGS.lock().unwrap().clone()
+}
+
+// the connection flags helm understands, under helm's own names, for the helm commands the tool spawns
+pub fn helm_args() -> Vec<String> {
+    to_helm_args(&GLOBAL_ARGS.lock().unwrap())
+}
+
+// helm has no --cluster or --user, so those are left for the kubeconfig to decide
+fn to_helm_args(args: &[String]) -> Vec<String> {
+    let mut helm = Vec::new();
+    for pair in args.chunks(2) {
+        let flag = match pair[0].as_str() {
+            "--context" => "--kube-context",
+            "--kubeconfig" => "--kubeconfig",
+            "--as" => "--kube-as-user",
+            "--as-group" => "--kube-as-group",
+            _ => continue,
+        };
+        if let Some(value) = pair.get(1) {
+            helm.push(flag.to_string());
+            helm.push(value.clone());
+        }
+    }
+    helm
+}
+
+// flags for kubectl get to reach the cluster through kubectl proxy instead, when it's running
+// kept with the global args the proxy stands in for, since it only serves the context it started on
+static PROXY_ARGS: Mutex<Option<(Vec<String>, Vec<String>)>> = Mutex::new(None);
+
+pub fn set_proxy_args(args: Option<Vec<String>>) {
+    let served = GLOBAL_ARGS.lock().unwrap().clone();
+    *PROXY_ARGS.lock().unwrap() = args.map(|args| (served, args));
+}
+
+// how long a kubectl command that runs to completion gets before it is killed, if at all
+static REQUEST_TIMEOUT: Mutex<Option<Duration>> = Mutex::new(None);
+
+pub fn set_request_timeout(timeout: Option<Duration>) {
+    *REQUEST_TIMEOUT.lock().unwrap() = timeout;
+}
+
+// at most this many kubectl commands are started each second, when set, so a big selection or a
+// burst of refreshes doesn't get throttled by the api server
+// up to a second's worth can start at once before the rest are spaced out
+static RATE_LIMIT: Mutex<Option<Limiter>> = Mutex::new(None);
+
+struct Limiter {
+    per_second: f64,
+    // commands that can start straight away, below zero when some are already waiting
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl Limiter {
+    // takes a token for a command starting at now, and how long it has to wait when none are left
+    fn take(&mut self, now: Instant) -> Option<Duration> {
+        let refill = now.duration_since(self.refilled).as_secs_f64() * self.per_second;
+        self.tokens = (self.tokens + refill).min(self.per_second) - 1.0;
+        self.refilled = now;
+        if self.tokens >= 0.0 {
+            None
+        } else {
+            Some(Duration::from_secs_f64(-self.tokens / self.per_second))
+        }
+    }
+}
+
+pub fn set_rate_limit(per_second: Option<u32>) {
+    *RATE_LIMIT.lock().unwrap() = per_second.filter(|n| *n > 0).map(|n| Limiter {
+        per_second: n as f64,
+        tokens: n as f64,
+        refilled: Instant::now(),
+    });
+}
+
+// waits until the rate limit allows another kubectl command to start
+// every command the tool spawns goes through here first
+pub fn throttle() {
+    let wait = RATE_LIMIT
+        .lock()
+        .unwrap()
+        .as_mut()
+        .and_then(|limiter| limiter.take(Instant::now()));
+    let wait = match wait {
+        Some(wait) => wait,
+        None => return,
+    };
+    debug!(?wait, "rate limited");
+    std::thread::sleep(wait);
+}
+
+// when set bindings print the kubectl commands they would run instead of running them
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+pub fn set_dry_run(dry_run: bool) {
+    DRY_RUN.store(dry_run, Ordering::SeqCst);
+}
+
+pub fn is_dry_run() -> bool {
+    DRY_RUN.load(Ordering::SeqCst)
+}
+
+// the quoted command line to print in place of running cmd, or None when it should really run
+pub fn dry_run(cmd: &Exec) -> Option<String> {
+    if is_dry_run() {
+        Some(format!("{}\n", cmd.to_cmdline_lossy()))
+    } else {
+        None
+    }
+}
+
+// when set the preview starts with how many items are selected, which skim works out as the
+// selection changes
+static COUNTS_SELECTED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_counts_selected(counts: bool) {
+    COUNTS_SELECTED.store(counts, Ordering::SeqCst);
+}
+
+// the stderr of the kubectl commands that failed since it was last taken, so a binding whose commands
+// failed can end the run with an error
+// a command that couldn't run at all or timed out has already said so and leaves an empty entry
+static FAILURES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+pub fn take_failures() -> Vec<String> {
+    std::mem::take(&mut *FAILURES.lock().unwrap())
+}
+
+// drops the last failure, for a command a binding went on to retry another way
+pub fn forget_failure() {
+    FAILURES.lock().unwrap().pop();
+}
+
 // provides the base command for kubectl as a Exec builder to expand on
 // kubectl -n <namespace>? <command> <resource>
 pub fn kubectl_base_cmd<T: Into<Option<String>>>(
@@ -22,7 +199,556 @@ pub fn kubectl_base_cmd<T: Into<Option<String>>>(
     if let Some(namespace) = namespace {
         builder = builder.arg("--namespace").arg(namespace);
     }
-    builder
+    // gets go through the proxy when there is one for the context, which carries the connection
+    // flags itself, and anything else or another context goes to the cluster as usual
+    let global_args = GLOBAL_ARGS.lock().unwrap().clone();
+    let global_args = match PROXY_ARGS.lock().unwrap().clone() {
+        Some((served, args)) if command == "get" && served == global_args => args,
+        _ => global_args,
+    };
+    builder.args(&global_args)
+}
+
+// runs a kubectl command to completion under the supervisor
+pub fn execute(cmd: Exec) -> Option<CaptureData> {
+    execute_with_input(cmd, None)
+}
+
+// runs a kubectl command to completion like execute, writing the input to its stdin
+// example: kubectl create -f - with a manifest
+pub fn execute_with_input(cmd: Exec, input: Option<&str>) -> Option<CaptureData> {
+    run(cmd, input, false)
+}
+
+// runs a command that may take a while to completion like execute, showing its latest line of
+// output on stderr as it goes rather than looking stuck until it's done
+// example: kubectl drain or kubectl rollout status from a --bind command
+pub fn execute_streamed(cmd: Exec) -> Option<CaptureData> {
+    run(cmd, None, true)
+}
+
+fn run(cmd: Exec, input: Option<&str>, streamed: bool) -> Option<CaptureData> {
+    let cmdline = cmd.to_cmdline_lossy();
+    throttle();
+    let started = Instant::now();
+    let timeout = *REQUEST_TIMEOUT.lock().unwrap();
+    let output = if streamed {
+        let output = supervisor::stream(cmd, input, timeout, progress::show_line);
+        progress::clear_line();
+        output
+    } else {
+        supervisor::capture(cmd, input, timeout)
+    };
+    if let Some(timeout) = timeout.filter(|t| output.is_none() && started.elapsed() >= *t) {
+        warn!(command = %cmdline, ?timeout, "kubectl timed out");
+        eprintln!("Timed out after {}s: {}", timeout.as_secs(), cmdline);
+    }
+    let status = output.as_ref().map(|o| &o.exit_status);
+    info!(command = %cmdline, elapsed = ?started.elapsed(), status = ?status, "kubectl");
+    match output.as_ref() {
+        Some(output) if !output.exit_status.success() => {
+            debug!(command = %cmdline, stderr = %output.stderr_str().trim_end(), "kubectl failed");
+            FAILURES.lock().unwrap().push(output.stderr_str());
+        }
+        Some(_) => {}
+        None => FAILURES.lock().unwrap().push(String::new()),
+    }
+    audit::record(&cmdline, status);
+    output
+}
+
+// runs a kubectl command that lists things, reusing its output from the cache while it's fresh
+pub fn execute_cached(cmd: Exec) -> Option<CaptureData> {
+    let cmdline = cache_key(&cmd);
+    if let Some(stdout) = cache::get(&cmdline) {
+        debug!(command = %cmdline, "cached");
+        return Some(CaptureData {
+            stdout: stdout.into_bytes(),
+            stderr: Vec::new(),
+            exit_status: ExitStatus::Exited(0),
+        });
+    }
+
+    let output = execute(cmd)?;
+    if output.exit_status.success() {
+        cache::put(&cmdline, &output.stdout_str());
+    }
+    Some(output)
+}
+
+// the command line a listing is cached under, with the connection flags the proxy stands in for
+// rather than the proxy's own kubeconfig, which is new each run
+fn cache_key(cmd: &Exec) -> String {
+    let cmdline = cmd.to_cmdline_lossy();
+    match PROXY_ARGS.lock().unwrap().as_ref() {
+        Some((served, args)) => {
+            let quoted = |args: &[String]| {
+                args.iter()
+                    .map(|a| format!(" {}", shell_quote(a)))
+                    .collect::<String>()
+            };
+            cmdline.replace(&quoted(args), &quoted(served))
+        }
+        None => cmdline,
+    }
+}
+
+// runs a kubectl command attached to the terminal, such as edit, until it exits
+pub fn interactive(cmd: Exec) -> Option<ExitStatus> {
+    let cmdline = cmd.to_cmdline_lossy();
+    throttle();
+    let started = Instant::now();
+    let status = supervisor::join(cmd);
+    info!(command = %cmdline, elapsed = ?started.elapsed(), status = ?status, "kubectl");
+    // its errors went straight to the terminal
+    if !status.map_or(false, |s| s.success()) {
+        FAILURES.lock().unwrap().push(String::new());
+    }
+    audit::record(&cmdline, status.as_ref());
+    status
+}
+
+// runs a kubectl command to completion and returns its stdout
+// in dry run mode the command line is returned instead
+pub fn capture(cmd: Exec) -> Option<String> {
+    if let Some(cmdline) = dry_run(&cmd) {
+        return Some(cmdline);
+    }
+    execute(cmd).map(|c| c.stdout_str())
+}
+
+// runs a kubectl command to completion like capture, showing its latest line on stderr as it goes
+// so bindings that take a while like drain or rollout status don't look stuck
+pub fn stream(cmd: Exec) -> Option<String> {
+    if let Some(cmdline) = dry_run(&cmd) {
+        return Some(cmdline);
+    }
+    execute_streamed(cmd).map(|c| c.stdout_str())
+}
+
+// quotes a single word for a shell, an empty one too so it stays an argument
+pub fn shell_quote<S: AsRef<OsStr>>(word: S) -> String {
+    if word.as_ref().is_empty() {
+        return "''".into();
+    }
+    Exec::cmd(word).to_cmdline_lossy()
+}
+
+// kubectl get -o json for the named items of a resource
+// a single name returns the object itself while several return a List, so flatten both into a vec of objects
+// these are lookups that bindings build on so they run even in dry run mode
+pub fn kubectl_get_json(
+    namespace: Option<&str>,
+    resource: &str,
+    names: &[String],
+) -> Option<Vec<Value>> {
+    let output = execute(
+        kubectl_base_cmd(namespace, "get", resource.to_string())
+            .arg("--output")
+            .arg("json")
+            .args(names),
+    )?;
+
+    json_items(&output.stdout_str())
+}
+
+// name and namespace of the context kubectl is pointed at
+pub fn current_context() -> Option<(String, String)> {
+    let output = execute(
+        kubectl_base_cmd(None, "config", "view".to_string())
+            .arg("--minify")
+            .arg("--output")
+            .arg("json"),
+    )?;
+    let config = json_items(&output.stdout_str())?.pop()?;
+    let context = &config["contexts"][0];
+
+    Some((
+        context["name"].as_str().unwrap_or_default().to_string(),
+        context["context"]["namespace"]
+            .as_str()
+            .unwrap_or("default")
+            .to_string(),
+    ))
+}
+
+// parses kubectl json output into a vec of objects whether it was a single object or a List
+pub fn json_items(output: &str) -> Option<Vec<Value>> {
+    let value: Value = serde_json::from_str(output).ok()?;
+    let is_list = value["kind"]
+        .as_str()
+        .map_or(false, |kind| kind.ends_with("List"));
+
+    if is_list {
+        value["items"].as_array().cloned()
+    } else {
+        Some(vec![value])
+    }
+}
+
+// strips the fields the server fills in from an object so what's left can be applied again
+// such as status, managedFields, uid and resourceVersion
+pub fn clean(item: &mut Value) {
+    const METADATA: [&str; 7] = [
+        "managedFields",
+        "resourceVersion",
+        "uid",
+        "creationTimestamp",
+        "generation",
+        "selfLink",
+        "ownerReferences",
+    ];
+    const ANNOTATIONS: [&str; 2] = [
+        "kubectl.kubernetes.io/last-applied-configuration",
+        "deployment.kubernetes.io/revision",
+    ];
+
+    if let Some(object) = item.as_object_mut() {
+        object.remove("status");
+    }
+    if let Some(annotations) = item["metadata"]["annotations"].as_object_mut() {
+        for annotation in &ANNOTATIONS {
+            annotations.remove(*annotation);
+        }
+    }
+    if let Some(metadata) = item["metadata"].as_object_mut() {
+        for field in &METADATA {
+            metadata.remove(*field);
+        }
+        let no_annotations = metadata
+            .get("annotations")
+            .and_then(|a| a.as_object())
+            .map_or(false, |a| a.is_empty());
+        if no_annotations {
+            metadata.remove("annotations");
+        }
+    }
+}
+
+// the named items of a resource with the server populated fields stripped by clean
+// a single item is returned as itself and several as a List, like kubectl get
+pub fn kubectl_get_clean(
+    namespace: Option<&str>,
+    resource: &str,
+    names: &[String],
+) -> Option<Value> {
+    let mut items = kubectl_get_json(namespace, resource, names)?;
+    items.iter_mut().for_each(clean);
+
+    if items.len() == 1 {
+        items.pop()
+    } else {
+        Some(json!({"apiVersion": "v1", "kind": "List", "items": items}))
+    }
+}
+
+// converts a label selector object into the string form accepted by kubectl --selector
+// {matchLabels: {app: api}, matchExpressions: [{key: tier, operator: In, values: [web]}]} -> app=api,tier in (web)
+// services use a plain map of labels instead which is treated the same as matchLabels
+pub fn label_selector(selector: &Value) -> Option<String> {
+    let mut requirements = Vec::new();
+
+    let is_label_selector =
+        selector.get("matchLabels").is_some() || selector.get("matchExpressions").is_some();
+    let labels = if is_label_selector {
+        &selector["matchLabels"]
+    } else {
+        selector
+    };
+
+    if let Some(labels) = labels.as_object() {
+        for (key, value) in labels {
+            requirements.push(format!("{}={}", key, value.as_str().unwrap_or_default()));
+        }
+    }
+
+    if let Some(expressions) = selector["matchExpressions"].as_array() {
+        for expression in expressions {
+            let key = expression["key"].as_str()?;
+            let values = expression["values"]
+                .as_array()
+                .map(|v| {
+                    v.iter()
+                        .filter_map(Value::as_str)
+                        .collect::<Vec<_>>()
+                        .join(",")
+                })
+                .unwrap_or_default();
+
+            requirements.push(match expression["operator"].as_str()? {
+                "In" => format!("{} in ({})", key, values),
+                "NotIn" => format!("{} notin ({})", key, values),
+                "Exists" => key.to_string(),
+                "DoesNotExist" => format!("!{}", key),
+                _ => return None,
+            });
+        }
+    }
+
+    if requirements.is_empty() {
+        None
+    } else {
+        Some(requirements.join(","))
+    }
+}
+
+// where each column of a kubectl table starts, counted in chars
+// columns are at least two spaces apart since headers like NOMINATED NODE have a space inside
+pub fn column_starts(header: &str) -> Vec<usize> {
+    let mut starts = Vec::new();
+    let mut spaces = 0;
+    for (i, c) in header.chars().enumerate() {
+        if c == ' ' {
+            spaces += 1;
+            continue;
+        }
+        if starts.is_empty() || spaces >= 2 {
+            starts.push(i);
+        }
+        spaces = 0;
+    }
+    starts
+}
+
+// the column names of a kubectl table header
+pub fn header_columns(header: &str) -> Vec<String> {
+    split_columns(header, header)
+}
+
+// splits a row into cells at the header's column offsets so values with spaces in them,
+// like event messages or node conditions, stay in their own column
+// helm separates with tabs, and rows that don't line up with the header such as later rows
+// of a watch fall back to splitting on whitespace
+pub fn split_columns(header: &str, row: &str) -> Vec<String> {
+    split_leading_columns(header, row, usize::MAX)
+}
+
+// the first count cells of a row, for when only the prefix and name are wanted
+pub fn split_leading_columns(header: &str, row: &str, count: usize) -> Vec<String> {
+    if header.contains('\t') {
+        return row
+            .split('\t')
+            .take(count)
+            .map(|c| c.trim().to_string())
+            .collect();
+    }
+
+    let all_starts = column_starts(header);
+    let starts = &all_starts[..all_starts.len().min(count)];
+    let chars: Vec<char> = row.chars().collect();
+    let aligned = starts
+        .iter()
+        .all(|&s| s == 0 || s >= chars.len() || chars[s - 1] == ' ');
+    if starts.is_empty() || !aligned {
+        return row
+            .split_whitespace()
+            .take(count)
+            .map(String::from)
+            .collect();
+    }
+    // the cell before the first one left out ends where that one starts
+    let bound = all_starts.get(starts.len()).copied();
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let end = starts
+                .get(i + 1)
+                .copied()
+                .or(bound)
+                .map_or(chars.len(), |e| e.min(chars.len()));
+            let start = start.min(end);
+            chars[start..end]
+                .iter()
+                .collect::<String>()
+                .trim()
+                .to_string()
+        })
+        .collect()
+}
+
+// lays cells out the way kubectl does, each column as wide as its widest cell and three spaces apart
+pub fn render_columns(rows: &[Vec<String>]) -> Vec<String> {
+    let mut widths: Vec<usize> = Vec::new();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            if i == widths.len() {
+                widths.push(0);
+            }
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+
+    rows.iter()
+        .map(|row| {
+            let mut line = String::new();
+            for (i, cell) in row.iter().enumerate() {
+                if i + 1 == row.len() {
+                    line.push_str(cell);
+                } else {
+                    line.push_str(&format!("{:<width$}   ", cell, width = widths[i]));
+                }
+            }
+            line
+        })
+        .collect()
+}
+
+// which of a listing's columns are shown
+// scroll hides that many columns after the first, and width leaves out the trailing columns
+// that don't fit, so wide rows don't run off the edge of the picker
+#[derive(Clone, Copy, Default)]
+pub struct View {
+    pub scroll: usize,
+    pub width: Option<usize>,
+}
+
+// what the rows of a listing have in common, worked out once from its header
+pub struct Layout {
+    ages: Option<Ages>,
+    starts: Vec<usize>,
+    // the columns shown, in order
+    shown: Vec<usize>,
+    restarts: Option<usize>,
+    thresholds: Restarts,
+    // the TYPE column of events, which colors it and the REASON after it by the type
+    types: Option<usize>,
+}
+
+impl Layout {
+    pub fn new(header: &str, resource: &str, thresholds: Restarts, view: View) -> Self {
+        // helm's tab separated columns have no offsets to go by
+        let starts = if header.contains('\t') {
+            Vec::new()
+        } else {
+            column_starts(header)
+        };
+        let columns = header_columns(header);
+        let restarts = columns
+            .iter()
+            .position(|c| c == "RESTARTS")
+            .filter(|_| !starts.is_empty());
+        let types = columns
+            .iter()
+            .position(|c| c == "TYPE")
+            .filter(|_| !starts.is_empty())
+            .filter(|_| resources::is_one_of(resource, &BindingContext::accepts_events()));
+
+        // the first column is the name so it always shows, then as many as fit after those scrolled past
+        let mut shown = Vec::new();
+        let mut used = 0;
+        let scroll = view.scroll.min(starts.len().saturating_sub(2));
+        for i in (0..starts.len()).filter(|&i| i == 0 || i > scroll) {
+            if used > 0 && view.width.map_or(false, |w| used >= w) {
+                break;
+            }
+            shown.push(i);
+            used += starts.get(i + 1).map_or(0, |next| next - starts[i]);
+        }
+
+        Layout {
+            ages: Ages::new(header),
+            starts,
+            shown,
+            restarts,
+            thresholds,
+            types,
+        }
+    }
+
+    // whether a row has restarted more than the warning threshold
+    pub fn restarting(&self, row: &str) -> bool {
+        self.restarts
+            .and_then(|i| restart_count(self.cells(row)?.get(i)?))
+            .map_or(false, |count| count > self.thresholds.warning)
+    }
+
+    pub fn has_restarts(&self) -> bool {
+        self.restarts.is_some()
+    }
+
+    // the row with only the columns in view and its restart count or event type colored
+    // none when it would be the same as the row
+    pub fn render(&self, row: &str, colored: bool) -> Option<String> {
+        let highlight = colored && (self.restarts.is_some() || self.types.is_some());
+        if self.shown.len() == self.starts.len() && !highlight {
+            return None;
+        }
+
+        let cells = self.cells(row)?;
+        let event = self
+            .types
+            .filter(|_| highlight)
+            .and_then(|t| Some((t, event_color(cells.get(t)?)?)));
+        let mut rendered = String::new();
+        for &i in &self.shown {
+            let cell = match cells.get(i) {
+                Some(cell) => cell,
+                None => break,
+            };
+            match (self.restarts.filter(|_| highlight), event) {
+                (Some(restarts), _) if restarts == i => rendered.push_str(&self.highlight(cell)),
+                (_, Some((t, color))) if i == t || i == t + 1 => {
+                    rendered.push_str(&format!("{}{}\x1b[0m", color, cell))
+                }
+                _ => rendered.push_str(cell),
+            }
+        }
+        Some(rendered.trim_end().to_string())
+    }
+
+    // the row cut at the column starts, each cell keeping the spaces that pad it
+    // none when the row doesn't line up with the header, like later rows of a watch
+    fn cells(&self, row: &str) -> Option<Vec<String>> {
+        let chars: Vec<char> = row.chars().collect();
+        let aligned = self
+            .starts
+            .iter()
+            .all(|&s| s == 0 || s >= chars.len() || chars[s - 1] == ' ');
+        if self.starts.is_empty() || !aligned {
+            return None;
+        }
+
+        let cells = self
+            .starts
+            .iter()
+            .enumerate()
+            .map(|(i, &start)| {
+                let end = self
+                    .starts
+                    .get(i + 1)
+                    .map_or(chars.len(), |&e| e.min(chars.len()));
+                chars[start.min(end)..end].iter().collect()
+            })
+            .collect();
+        Some(cells)
+    }
+
+    // colors a RESTARTS cell yellow above the warning threshold and red above the critical one
+    fn highlight(&self, cell: &str) -> String {
+        let count = match restart_count(cell) {
+            Some(count) => count,
+            None => return cell.to_string(),
+        };
+        let color = if count > self.thresholds.critical {
+            "\x1b[31m"
+        } else if count > self.thresholds.warning {
+            "\x1b[33m"
+        } else {
+            return cell.to_string();
+        };
+
+        let digits = cell.chars().take_while(|c| c.is_ascii_digit()).count();
+        format!("{}{}\x1b[0m{}", color, &cell[..digits], &cell[digits..])
+    }
+}
+
+// the count a RESTARTS cell starts with
+// example: 3 (5m ago) -> 3
+fn restart_count(cell: &str) -> Option<u64> {
+    let digits = cell.chars().take_while(|c| c.is_ascii_digit()).count();
+    cell[..digits].parse().ok()
 }
 
 // encapsulates the result of a kubectl get output list
@@ -30,6 +756,8 @@ pub fn kubectl_base_cmd<T: Into<Option<String>>>(
 pub struct KubectlOutput {
     pub header: String,
     pub items: Vec<KubectlItem>,
+    // how many items were listed before any filtering here, such as --restarting
+    pub total: usize,
 }
 
 // provider an encapsulation over a row in kubectl get
@@ -40,22 +768,64 @@ pub struct KubectlItem {
     inner: String,
     resource: String,
     bindings: Arc<Mutex<BindingMap>>,
+    // the layout of the listing and when the row was listed, to show ages as of now
+    layout: Arc<Layout>,
+    listed: Instant,
+    // still selected from the last visit to the listing, which skim can't be told about
+    kept: bool,
 }
 
 impl KubectlItem {
-    pub fn new(inner: String, resource: String, bindings: Arc<Mutex<BindingMap>>) -> Self {
+    pub fn new(
+        inner: String,
+        resource: String,
+        bindings: Arc<Mutex<BindingMap>>,
+        layout: Arc<Layout>,
+    ) -> Self {
         KubectlItem {
             inner,
             resource,
             bindings,
+            layout,
+            listed: Instant::now(),
+            kept: false,
         }
     }
+
+    // marks the row as still selected, shown in bold since skim has no marker for it
+    pub fn keep(&mut self) {
+        self.kept = true;
+    }
 }
 
 // implement skim trait so we use it in skim and as returned selected items
 impl SkimItem for KubectlItem {
     fn display(&self) -> Cow<AnsiString> {
-        Cow::Owned(self.inner.as_str().into())
+        // skim redraws as the picker is used so the ages keep counting while it's open
+        let row = self
+            .layout
+            .ages
+            .as_ref()
+            .and_then(|ages| ages.advance(&self.inner, self.listed))
+            .unwrap_or_else(|| self.inner.clone());
+
+        let colored = theme::colored();
+        let row = match self.layout.render(&row, colored) {
+            Some(rendered) => rendered,
+            // rows that don't line up with the header, like later rows of a watch, find the event
+            // type by counting words instead
+            None => match self.layout.types.filter(|_| colored) {
+                Some(types) => colorize_event(&row, types).unwrap_or(row),
+                None => row,
+            },
+        };
+
+        // bold again after every reset of the colored cells
+        if self.kept {
+            let row = row.replace("\x1b[0m", "\x1b[0;1m");
+            return Cow::Owned(AnsiString::parse(&format!("\x1b[1m{}\x1b[0m", row)));
+        }
+        Cow::Owned(AnsiString::parse(&row))
     }
 
     fn text(&self) -> Cow<str> {
@@ -70,8 +840,10 @@ impl SkimItem for KubectlItem {
 
         // inject global always available bindings from skim
         // gross way to do it
-        let toggle_preview = "\u{1b}[31mToggle Preview\t\u{1b}[33mctrl-p\u{1b}[0m".to_string();
-        let always_keys: Vec<String> = vec![toggle_preview];
+        let always_keys: Vec<String> = PICKER_KEYS
+            .iter()
+            .map(|(description, key, _)| theme::binding_preview(description, key))
+            .collect();
 
         // get preview for each binding this resource works for and return a newline per result
         let mut sorted_previews = self
@@ -92,7 +864,11 @@ impl SkimItem for KubectlItem {
 
         let tabbed_str = String::from_utf8(tab_writer.into_inner().unwrap()).unwrap();
 
-        ItemPreview::AnsiText(tabbed_str)
+        if COUNTS_SELECTED.load(Ordering::SeqCst) {
+            ItemPreview::Command(counting_selected(&tabbed_str))
+        } else {
+            ItemPreview::AnsiText(tabbed_str)
+        }
     }
 
     // output is what's returned from selected items (unless you do some trait downcasting)
@@ -102,3 +878,185 @@ impl SkimItem for KubectlItem {
         //Cow::Borrowed(self.inner.split_whitespace().next().unwrap_or(&self.inner))
     }
 }
+
+// a preview command printing how many items are selected above the preview text
+// skim fills {+n} with the index of each selected item, or the one under the cursor when none are
+// braces in the text are quoted on their own so skim doesn't fill them in too
+// example: 3 selected
+fn counting_selected(text: &str) -> String {
+    let quoted = text.replace('\'', "'\"'\"'").replace('{', "'\"{\"'");
+    format!(
+        "printf '%s\\n' {{+n}} | wc -l | xargs printf '%s selected\\n\\n'; printf '%s' '{}'",
+        quoted
+    )
+}
+
+// the color of an event's TYPE cell so warnings stand out in the firehose
+// warnings are red and normal events are green
+fn event_color(cell: &str) -> Option<&'static str> {
+    match cell.trim() {
+        "Warning" => Some("\x1b[31m"),
+        "Normal" => Some("\x1b[32m"),
+        _ => None,
+    }
+}
+
+// colors the TYPE and REASON of an event row that doesn't line up with the header, taking the
+// type as the word at the TYPE column's place and the reason as the word after it
+fn colorize_event(row: &str, types: usize) -> Option<String> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in row.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                words.push((s, i));
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        words.push((s, row.len()));
+    }
+
+    let (type_start, type_end) = *words.get(types)?;
+    let color = event_color(&row[type_start..type_end])?;
+    let reason_end = words.get(types + 1).map_or(type_end, |&(_, end)| end);
+    Some(format!(
+        "{}{}{}\x1b[0m{}",
+        &row[..type_start],
+        color,
+        &row[type_start..reason_end],
+        &row[reason_end..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_columns_keep_names_with_spaces() {
+        assert_eq!(
+            header_columns("NAME   NOMINATED NODE   AGE"),
+            vec!["NAME", "NOMINATED NODE", "AGE"]
+        );
+    }
+
+    #[test]
+    fn split_columns_keeps_spaces_inside_cells() {
+        assert_eq!(
+            split_columns(
+                "LAST SEEN   TYPE     REASON   MESSAGE",
+                "5m          Normal   Pulled   Pulled image nginx"
+            ),
+            vec!["5m", "Normal", "Pulled", "Pulled image nginx"]
+        );
+    }
+
+    #[test]
+    fn events_are_colored_by_their_type_column() {
+        let layout = Layout::new(
+            "LAST SEEN   TYPE      REASON   MESSAGE",
+            "events",
+            Restarts::default(),
+            View::default(),
+        );
+        // the message mentioning a normal exit doesn't make the warning green
+        assert_eq!(
+            layout
+                .render("5m          Warning   Failed   Normal exit", true)
+                .unwrap(),
+            "5m          \x1b[31mWarning   \x1b[0m\x1b[31mFailed   \x1b[0mNormal exit"
+        );
+        assert_eq!(
+            colorize_event("5m Warning Failed Normal exit", 1).unwrap(),
+            "5m \x1b[31mWarning Failed\x1b[0m Normal exit"
+        );
+    }
+
+    #[test]
+    fn split_columns_falls_back_to_whitespace_when_misaligned() {
+        assert_eq!(
+            split_columns("NAME   STATUS", "a-very-long-name Running"),
+            vec!["a-very-long-name", "Running"]
+        );
+    }
+
+    #[test]
+    fn split_columns_splits_helm_on_tabs() {
+        assert_eq!(
+            split_columns("NAME\tNAMESPACE\tREVISION", "web\tdefault \t3"),
+            vec!["web", "default", "3"]
+        );
+    }
+
+    #[test]
+    fn split_leading_columns_stops_at_the_next_column() {
+        assert_eq!(
+            split_leading_columns("NAME   READY   STATUS", "api-0  1/1     Running", 1),
+            vec!["api-0"]
+        );
+    }
+
+    #[test]
+    fn limiter_allows_a_burst_then_spaces_out() {
+        let start = Instant::now();
+        let mut limiter = Limiter {
+            per_second: 2.0,
+            tokens: 2.0,
+            refilled: start,
+        };
+        assert_eq!(limiter.take(start), None);
+        assert_eq!(limiter.take(start), None);
+        assert_eq!(limiter.take(start), Some(Duration::from_millis(500)));
+        // the token refilled since went to the one already waiting
+        assert_eq!(
+            limiter.take(start + Duration::from_millis(500)),
+            Some(Duration::from_millis(500))
+        );
+    }
+
+    #[test]
+    fn limiter_saves_up_no_more_than_a_second() {
+        let start = Instant::now();
+        let mut limiter = Limiter {
+            per_second: 2.0,
+            tokens: 0.0,
+            refilled: start,
+        };
+        let later = start + Duration::from_secs(10);
+        assert_eq!(limiter.take(later), None);
+        assert_eq!(limiter.take(later), None);
+        assert_eq!(limiter.take(later), Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn helm_args_rename_the_flags_helm_knows() {
+        let args: Vec<String> = vec![
+            "--context",
+            "prod",
+            "--kubeconfig",
+            "/tmp/config",
+            "--cluster",
+            "east",
+            "--as",
+            "admin",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        assert_eq!(
+            to_helm_args(&args),
+            vec![
+                "--kube-context",
+                "prod",
+                "--kubeconfig",
+                "/tmp/config",
+                "--kube-as-user",
+                "admin"
+            ]
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::Level;
+
+// sets up tracing for -v and --log-file
+// nothing is logged without either, each -v shows more and a log file on its own gets debug
+// without a log file it goes to stderr, which draws over the picker unless redirected
+pub fn init(verbose: u64, log_file: Option<&Path>) -> Result<(), String> {
+    let level = match (verbose, log_file) {
+        (0, None) => return Ok(()),
+        (0, Some(_)) => Level::DEBUG,
+        (1, _) => Level::INFO,
+        (2, _) => Level::DEBUG,
+        _ => Level::TRACE,
+    };
+
+    let builder = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_target(false);
+
+    match log_file {
+        Some(path) => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| format!("Failed to open log file {}: {}", path.display(), e))?;
+            builder
+                .with_ansi(false)
+                .with_writer(Mutex::new(file))
+                .init();
+        }
+        None => builder.with_writer(std::io::stderr).init(),
+    }
+
+    Ok(())
+}
@@ -1,7 +1,18 @@
-use clap::Clap;
+use clap::{AppSettings, Clap, IntoApp};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use regex::{Regex, RegexBuilder};
+use serde_json::Value;
 use skim::prelude::*;
 use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
 use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use subprocess::{CaptureData, Exec, NullFile, Popen, Redirection};
+use tracing::{debug, info, warn};
 
 mod kubectl;
 use kubectl::*;
@@ -9,6 +20,66 @@ use kubectl::*;
 mod bindings;
 use bindings::*;
 
+mod config;
+use config::{Config, Confirm, ResourceDefaults};
+
+mod age;
+mod audit;
+mod bookmarks;
+mod cache;
+mod clip;
+mod completions;
+mod frecency;
+mod logging;
+mod pager;
+mod picker;
+mod plugins;
+mod progress;
+mod proxy;
+mod recent;
+mod resources;
+mod scripts;
+mod session;
+mod shell_init;
+mod sort;
+mod summary;
+mod supervisor;
+mod table;
+mod theme;
+mod tmux;
+
+// keys skim needs for itself, so bindings can't be remapped onto them
+// as well as the picker keys bound to skim actions
+const RESERVED_KEYS: [&str; 9] = [
+    "enter", "esc", "ctrl-c", "ctrl-g", "ctrl-m", "tab", "btab", "up", "down",
+];
+
+// exit codes so scripts can tell what happened
+const EXIT_ABORTED: i32 = 1;
+const EXIT_NO_MATCH: i32 = 2;
+const EXIT_FAILED: i32 = 3;
+
+// what to do after a pass through the picker
+enum Outcome {
+    // print the output of a binding and exit
+    Output(String),
+    // output for other programs to read, printed as it is without a pager
+    Structured(String),
+    // relaunch the picker on the listing a binding navigated to
+    Navigate(Navigation),
+    // the picker was closed without choosing anything
+    Aborted,
+    // there was nothing to choose from
+    NoMatch,
+    // a binding couldn't run, with the reason why
+    Failed(String),
+    // a binding ran but kubectl failed on some of it, with the output and what kubectl said went wrong
+    Errored(String, String),
+}
+
+// selected rows from the same context and namespace, with those columns taken off
+type RowGroup = (Option<String>, Option<String>, Vec<Vec<String>>);
+
 #[derive(Clap)]
 #[clap(version = "0.1", author = "Jacobious52")]
 struct Opts {
@@ -18,58 +89,789 @@ struct Opts {
     #[clap(short, long)]
     wide: bool,
 
-    #[clap(default_value = "pod")]
-    resource: String,
+    /// Only list the items that have restarted more than the warning threshold, ctrl-n toggles it in the picker
+    #[clap(long)]
+    restarting: bool,
+
+    /// List the items bindings were run on most often and most lately first, unless sorting by something else
+    #[clap(long)]
+    frecent: bool,
+
+    /// Pick from a table read from stdin, like a saved kubectl get, instead of running kubectl.
+    /// Only the bindings that work on the rows themselves are offered
+    #[clap(long)]
+    stdin: bool,
+
+    /// Leave out the trailing columns that don't fit the picker rather than letting rows run off the edge. shift-left and shift-right scroll through the columns
+    #[clap(long)]
+    fit: bool,
+
+    /// Hide the line above the header saying how many items were listed and what narrowed them down
+    #[clap(long)]
+    no_status: bool,
+
+    /// Fetch the items as json and lay out the table here rather than parsing kubectl's, for kubectl's usual columns of pods, deployments and nodes, or --columns of any resource
+    #[clap(long)]
+    from_json: bool,
+
+    /// Show the labels of each item as the last column, so they can be searched and exported
+    #[clap(long)]
+    show_labels: bool,
+
+    /// Run the named binding on every item matching --filter without opening the picker, for example describe.
+    /// With --select-1 it runs on the single match instead, or when accepting in the picker
+    #[clap(long, value_name = "binding")]
+    action: Option<String>,
+
+    /// Query to select items with in batch mode. Runs the names binding unless --action is given
+    #[clap(long, value_name = "query")]
+    filter: Option<String>,
+
+    /// Match the query as exact substrings rather than fuzzily, in the picker and in batch mode
+    #[clap(short, long)]
+    exact: bool,
+
+    /// Match the query as a regex rather than fuzzily, in the picker and in batch mode
+    #[clap(long, conflicts_with = "exact")]
+    regex: bool,
+
+    /// How the query matches case. smart ignores case unless the query has capitals
+    #[clap(long, default_value = "smart", possible_values = &["smart", "sensitive", "insensitive"])]
+    case: String,
+
+    /// Refuse to run a binding on more than this many items at once. Some bindings that delete things have a lower limit of their own
+    #[clap(long, value_name = "n")]
+    max_select: Option<usize>,
+
+    /// Skip the picker when the query matches exactly one item
+    #[clap(short = '1', long)]
+    select_1: bool,
+
+    /// Exit with 0 rather than 2 when there is nothing to choose from
+    #[clap(long)]
+    exit_0: bool,
+
+    /// Allow bindings that change things to run in batch mode, and skip their confirmations
+    #[clap(long)]
+    yes: bool,
+
+    /// Print the kubectl commands bindings would run instead of running them
+    #[clap(long)]
+    dry_run: bool,
+
+    /// Print only what bindings output, without the state of changed items after them or why a binding didn't run.
+    /// The exit code still says what happened
+    #[clap(short, long)]
+    quiet: bool,
+
+    /// Log kubectl calls and bindings to stderr. Repeat for more detail
+    #[clap(short, long, parse(from_occurrences))]
+    verbose: u64,
+
+    /// Append the log to this file instead of stderr
+    #[clap(long, value_name = "path")]
+    log_file: Option<PathBuf>,
+
+    /// Return to the picker after running a binding instead of exiting. Esc exits
+    #[clap(long = "loop")]
+    loop_mode: bool,
+
+    /// Shell command to pipe the output of bindings through before printing, for example 'jq .status'
+    #[clap(long, value_name = "command")]
+    pipe: Option<String>,
+
+    /// Pager for output taller than the terminal. Defaults to $PAGER, then bat if installed, then less -R
+    #[clap(long)]
+    pager: Option<String>,
+
+    /// Always print output straight to stdout instead of paging it
+    #[clap(long)]
+    no_pager: bool,
+
+    /// List from all namespaces. Rows get a NAMESPACE column and bindings run in each item's namespace
+    #[clap(short = 'A', long)]
+    all_namespaces: bool,
+
+    /// List from several namespaces at once, like team-a,team-b. Rows get a NAMESPACE column and bindings run in each item's namespace
+    #[clap(long, value_name = "namespaces", use_delimiter = true, conflicts_with_all = &["namespace", "all-namespaces"])]
+    namespaces: Vec<String>,
+
+    /// List from several kubeconfig contexts at once, like staging,prod. Rows get a CONTEXT column and bindings run in each item's context
+    #[clap(long, value_name = "contexts", use_delimiter = true)]
+    contexts: Vec<String>,
+
+    /// List from every kubeconfig context at once, the same as --contexts with all of them
+    #[clap(long, conflicts_with = "contexts")]
+    all_contexts: bool,
+
+    /// Kubeconfig file for every kubectl call instead of $KUBECONFIG
+    #[clap(long, value_name = "path")]
+    kubeconfig: Option<PathBuf>,
+
+    /// Choose from the items bindings were recently run on to list them again, instead of giving the resource
+    #[clap(long)]
+    history: bool,
+
+    /// Choose a bookmark to jump to, instead of giving the resource
+    #[clap(long, conflicts_with = "history")]
+    bookmarks: bool,
+
+    /// Reopen the listing the picker was last closed on, with its context, namespace and query
+    #[clap(long, conflicts_with_all = &["history", "bookmarks"])]
+    resume: bool,
+
+    /// Kubeconfig context for every kubectl call
+    #[clap(long, value_name = "name")]
+    context: Option<String>,
+
+    /// Use a profile from the config, which sets the context, namespace, resource, keys and theme. Defaults to $KUBECTL_SELECT_PROFILE
+    #[clap(long, value_name = "name")]
+    profile: Option<String>,
+
+    /// Kubeconfig cluster for every kubectl call
+    #[clap(long, value_name = "name")]
+    cluster: Option<String>,
+
+    /// Kubeconfig user for every kubectl call
+    #[clap(long, value_name = "name")]
+    user: Option<String>,
+
+    /// Cache listings for this long, like 5s, so running again straight away doesn't wait on the cluster. Off unless given
+    #[clap(long, value_name = "duration", parse(try_from_str = parse_duration))]
+    cache_ttl: Option<Duration>,
+
+    /// Give up on kubectl calls that take longer than this, like 30s or 2m, by killing them. Interactive commands like exec and edit aren't limited
+    #[clap(long, value_name = "duration", parse(try_from_str = parse_duration))]
+    request_timeout: Option<Duration>,
+
+    /// Send every kubectl get through one kubectl proxy for the run, so each skips the TLS handshake and auth plugins of the cluster
+    #[clap(long)]
+    proxy: bool,
+
+    /// Send every kubectl get through a kubectl proxy already running at this address, like 127.0.0.1:8001
+    #[clap(long, value_name = "address")]
+    proxy_address: Option<String>,
+
+    /// Start at most this many kubectl calls a second, to stay under the api server's rate limits when acting on many items
+    #[clap(long, value_name = "n")]
+    rate_limit: Option<u32>,
+
+    /// Impersonate a user for every kubectl call, to check what they can see and do
+    #[clap(long = "as", value_name = "user", conflicts_with = "as-readonly-sa")]
+    as_user: Option<String>,
+
+    /// Impersonate a group for every kubectl call. Can be given more than once
+    #[clap(long, value_name = "group", multiple_occurrences = true)]
+    as_group: Vec<String>,
+
+    /// Impersonate a low privilege service account for every kubectl call and disable mutating bindings.
+    /// Takes <name> or <namespace>:<name>
+    #[clap(long, value_name = "name")]
+    as_readonly_sa: Option<String>,
+
+    /// Order to hand selected items to bindings and print their names in, as listed or in the order they were selected with tab
+    #[clap(long, default_value = "listed", possible_values = &["listed", "selected"])]
+    order: String,
+
+    /// Print the accepted items as names, or as a json list of objects with the name, namespace, resource and columns of each
+    #[clap(short, long, default_value = "names", possible_values = &["names", "json"])]
+    output: String,
+
+    /// Print a line for each accepted item from a template like '{{.name}} ({{.namespace}})'. Columns are fields by their header in lower case, like {{.node}} or {{.nominated_node}}
+    #[clap(long, value_name = "template", conflicts_with = "output")]
+    format: Option<String>,
+
+    /// Separate accepted names with a NUL byte instead of a newline, for xargs -0
+    #[clap(long)]
+    print0: bool,
+
+    /// Separate accepted names with this instead of a newline, for example ' ' to print them on one line
+    #[clap(long, value_name = "separator")]
+    delimiter: Option<String>,
+
+    /// Only show log lines matching this regex when following logs, with the matches highlighted
+    #[clap(long, value_name = "regex")]
+    grep: Option<Regex>,
+
+    /// Only show the keys when decoding secrets
+    #[clap(long)]
+    redact: bool,
+
+    /// Apply a JSONPath template like '{.spec.nodeName}' to each item in the json binding
+    #[clap(long, value_name = "template", conflicts_with = "jq")]
+    jsonpath: Option<String>,
+
+    /// Apply a jq filter like '.spec.nodeName' to each item in the json binding, needs jq installed
+    #[clap(long, value_name = "filter")]
+    jq: Option<String>,
+
+    /// Sort the rows by a column like RESTARTS, or by a JSONPath like .metadata.creationTimestamp that kubectl sorts by. Prefix a column with - to sort descending
+    #[clap(long, value_name = "column", allow_hyphen_values = true)]
+    sort_by: Option<String>,
+
+    /// List with custom columns like NODE:.spec.nodeName,IP:.status.podIP. The name is always the first column
+    #[clap(long, value_name = "spec")]
+    columns: Option<String>,
+
+    /// Strip status, managedFields and other fields the server fills in from the json and yaml bindings, leaving something that can be applied again
+    #[clap(long)]
+    clean: bool,
+
+    // the resource given on the command line, or by the profile
+    #[clap(name = "resource")]
+    resource_arg: Option<String>,
 
     query: Vec<String>,
 
+    /// Only list items matching the label selector
+    #[clap(short = 'l', long)]
+    selector: Option<String>,
+
+    /// Only list items matching the field selector
+    #[clap(long)]
+    field_selector: Option<String>,
+
+    /// Extra flags for kubectl get, given after --
+    #[clap(last = true, value_name = "kubectl args")]
+    get_args: Vec<String>,
+
+    // the resource being listed, which navigation moves on from the one given
+    #[clap(skip)]
+    resource: String,
+
+    // restricts the listing to these names when navigated to from another resource
+    #[clap(skip)]
+    names: Vec<String>,
+
+    // columns scrolled past after the first, for the current listing only
+    #[clap(skip)]
+    scroll: usize,
+
+    // lines of the table read with --stdin, listed instead of running kubectl
+    #[clap(skip)]
+    table: Option<Vec<String>>,
+
+    // names piped on stdin and the resource they restrict the listing of
+    #[clap(skip)]
+    piped: Option<(String, Vec<Regex>)>,
+
+    // set when wide output was toggled in the picker, for the current listing only
+    #[clap(skip)]
+    wide_toggled: Option<bool>,
+
+    // header of the current listing, which gives the offsets rows are split into columns at
+    #[clap(skip)]
+    header: String,
+
+    // the items selected on the current listing, by their leading columns, kept with the query
+    // for coming back to it
+    #[clap(skip)]
+    selected: Vec<String>,
+
+    // listings visited before and after the current one for back and forward
+    #[clap(skip)]
+    back: Vec<Navigation>,
+    #[clap(skip)]
+    forward: Vec<Navigation>,
+
     #[clap(skip)]
     bindings: Arc<Mutex<HashMap<String, Arc<dyn Binding + Send + Sync>>>>,
+
+    /// Height of the picker as lines or a percentage of the terminal. Defaults to 30%
+    #[clap(long)]
+    height: Option<String>,
+
+    /// Use the whole terminal for the picker and restore it afterwards
+    #[clap(long)]
+    full_screen: bool,
+
+    /// Position and size of the preview, for example bottom:50% or right:30%:hidden to start hidden. Defaults to right:20%
+    #[clap(long, value_name = "layout")]
+    preview_window: Option<String>,
+
+    /// Color preset for the picker and preview, overriding the theme in the config
+    #[clap(long, possible_values = &theme::PRESETS)]
+    theme: Option<String>,
+
+    /// Turn off colors in the picker, preview and output. Also turned off by setting NO_COLOR
+    #[clap(long)]
+    no_color: bool,
+
+    /// Open the picker in a tmux popup, or a split on tmux older than 3.2, when running inside tmux
+    #[clap(long)]
+    tmux: bool,
+
+    /// Add a binding for this run that runs a shell command on the selection, with {context}, {resource},
+    /// {namespace} and {names} filled in. For example 'ctrl-t:kubectl delete {resource} {names} -n {namespace} --context {context}'
+    #[clap(long, value_name = "key:command", multiple_occurrences = true)]
+    bind: Vec<String>,
+
+    /// Print a widget for the shell that inserts the selected names at the cursor on alt-k,
+    /// for example eval "$(kubectl-select --shell-init zsh)"
+    #[clap(long, value_name = "shell", possible_values = &["bash", "zsh", "fish"])]
+    shell_init: Option<String>,
+
+    #[clap(skip)]
+    config: Config,
+
+    // bindings loaded from the scripts and plugins in the config directory
+    #[clap(skip)]
+    extensions: Vec<Arc<dyn Binding + Send + Sync>>,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Clap)]
+enum Command {
+    /// Print a shell completion script, for example source <(kubectl-select completions bash)
+    Completions {
+        #[clap(possible_values = &["bash", "zsh", "fish"])]
+        shell: String,
+    },
+    /// Reverse the last binding in the audit log, such as uncordoning what was cordoned
+    Undo,
+    /// Manage the cache of listings
+    Cache {
+        #[clap(subcommand)]
+        command: CacheCommand,
+    },
+    // called by the completion scripts for resource types and namespaces from the cluster
+    #[clap(name = "__complete", setting = AppSettings::Hidden)]
+    Complete {
+        #[clap(possible_values = &["resources", "namespaces"])]
+        kind: String,
+    },
+}
+
+#[derive(Clap)]
+enum CacheCommand {
+    /// Remove every cached listing
+    Clear,
 }
 
 impl Opts {
     // adds the key bindings for skim to use as actions
-    fn setup_bindings(&mut self) {
-        self.add_binding(Names);
-        self.add_binding(Json);
-        self.add_binding(Yaml);
-        self.add_binding(Describe);
-        self.add_binding(Edit);
-        self.add_binding(Logs);
-        self.add_binding(Cordon);
-        self.add_binding(Uncordon);
-        self.add_binding(Copy);
+    fn setup_bindings(&mut self) -> Result<(), String> {
+        // start from scratch since column bindings differ between listings
+        self.bindings.lock().unwrap().clear();
+
+        if self.output == "json" {
+            self.add_binding(Names::json())?;
+        } else if let Some(format) = &self.format {
+            self.add_binding(Names::template(self.names_delimiter(), format.clone()))?;
+        } else {
+            self.add_binding(Names::new(self.names_delimiter()))?;
+        }
+
+        // without kubectl only the bindings that work on the rows themselves can run
+        if self.table.is_some() {
+            self.add_binding(Copy)?;
+            self.add_binding(CopyRows)?;
+            for bind in self.bind.clone() {
+                if let Ok(template) = Template::parse(&bind) {
+                    self.add_binding(template)?;
+                }
+            }
+            return Ok(());
+        }
+
+        self.add_binding(Json::new(self.clean, self.json_query()))?;
+        self.add_binding(Yaml::new(self.clean))?;
+        self.add_binding(Describe)?;
+        self.add_binding(Edit)?;
+        self.add_binding(Logs::new(self.grep.clone()))?;
+        self.add_binding(Cordon)?;
+        self.add_binding(Uncordon)?;
+        self.add_binding(Copy)?;
+        self.add_binding(CopyRows)?;
+        self.add_binding(CopyYaml)?;
+        self.add_binding(CopyCommand)?;
+        self.add_binding(Top)?;
+        self.add_binding(TriggerJob)?;
+        self.add_binding(Suspend)?;
+        self.add_binding(Resume)?;
+        self.add_binding(PauseRollout)?;
+        self.add_binding(ResumeRollout)?;
+        self.add_binding(Evict)?;
+        self.add_binding(ForceDelete)?;
+        self.add_binding(RemoveFinalizers)?;
+        self.add_binding(Patch)?;
+        self.add_binding(LastApplied)?;
+        self.add_binding(DiffLastApplied)?;
+        self.add_binding(SecretDecode::new(self.redact))?;
+        self.add_binding(Node)?;
+        self.add_binding(Pods)?;
+        self.add_binding(InvolvedObject)?;
+        self.add_binding(Endpoints)?;
+        self.add_binding(OwnerChain)?;
+        self.add_binding(Owner)?;
+        self.add_binding(KubectlPlugin::new(self.config.kubectl_plugins.clone()))?;
+        self.add_binding(Helm::status())?;
+        self.add_binding(Helm::values())?;
+        self.add_binding(Helm::history())?;
+        self.add_binding(Helm::rollback())?;
+        self.add_binding(Back)?;
+        self.add_binding(Forward)?;
+        self.add_binding(Recent)?;
+        self.add_binding(Bookmark)?;
+        self.add_binding(Bookmarks)?;
+
+        for extension in self.extensions.clone() {
+            self.add_shared(extension)?;
+        }
+        for bind in self.bind.clone() {
+            if let Ok(template) = Template::parse(&bind) {
+                self.add_binding(template)?;
+            }
+        }
+        Ok(())
+    }
+
+    // bindings disabled in the config are left out, overridden ones run the configured command instead
+    // and remapped ones are moved to their new key
+    // a binding whose key is already taken is left out and the clash returned
+    fn add_binding<T: Binding + Send + Sync + 'static>(&mut self, b: T) -> Result<(), String> {
+        self.add_shared(Arc::new(b))
+    }
+
+    fn add_shared(&mut self, b: Arc<dyn Binding + Send + Sync>) -> Result<(), String> {
+        if self.disabled(&b.name(), b.mutates()) {
+            return Ok(());
+        }
+        let b: Arc<dyn Binding + Send + Sync> = match self.config.overrides.get(&b.name()) {
+            Some(command) => Arc::new(Overridden::new(b, command.clone())),
+            None => b,
+        };
+        let b: Arc<dyn Binding + Send + Sync> = match self.config.keys.get(&b.name()) {
+            Some(key) if key.is_empty() => return Ok(()),
+            Some(key) => Arc::new(Rebound::new(b, key.clone())),
+            None => b,
+        };
+        let mut bindings = self.bindings.lock().unwrap();
+        if let Some(other) = bindings.get(&b.key()) {
+            return Err(format!(
+                "{} and {} are both bound to {}",
+                other.name(),
+                b.name(),
+                b.key()
+            ));
+        }
+        bindings.insert(b.key(), b);
+        Ok(())
+    }
+
+    // whether the config leaves a binding out, by its name or because it changes things
+    fn disabled(&self, name: &str, mutates: bool) -> bool {
+        self.config
+            .disabled
+            .iter()
+            .any(|d| d == name || (d == "mutating" && mutates))
     }
 
-    fn add_binding<T: Binding + Send + Sync + 'static>(&mut self, b: T) {
-        if self.bindings.lock().unwrap().contains_key(&b.key()) {
-            panic!("key {} already bound", b.key());
+    // bindings that come and go with the listing, added once it's listed
+    // check_keys has already reported any clash, so one that's left is skipped rather than ending the run
+    fn add_listing_binding<T: Binding + Send + Sync + 'static>(&mut self, b: T) {
+        if let Err(e) = self.add_binding(b) {
+            debug!(error = %e, "skipped binding");
         }
-        self.bindings.lock().unwrap().insert(b.key(), Arc::new(b));
+    }
+
+    // catches mistakes in the remapped keys at startup rather than part way through a run
+    fn check_keys(&mut self) -> Result<(), String> {
+        if self.config.keys.contains_key("names") {
+            return Err("names is always on enter and can't be remapped".into());
+        }
+        if self.config.disabled.iter().any(|d| d == "names") {
+            return Err("names is always on enter and can't be disabled".into());
+        }
+
+        // find the default keys by setting up the built in bindings without any remapping
+        let keys = std::mem::take(&mut self.config.keys);
+        let disabled = std::mem::take(&mut self.config.disabled);
+        let extensions = std::mem::take(&mut self.extensions);
+        let bind = std::mem::take(&mut self.bind);
+        self.setup_bindings()?;
+        self.add_listing_bindings(vec!["NAME".into()], vec!["NAME".into()], true);
+        // every column binding a listing could have, so their keys are checked too
+        let columns: Vec<String> = (0..20).map(|i| i.to_string()).collect();
+        self.add_columns(&columns);
+        let mut defaults: Vec<(String, String, bool)> = self
+            .bindings
+            .lock()
+            .unwrap()
+            .values()
+            .map(|b| (b.name(), b.key(), b.mutates()))
+            .collect();
+        self.config.keys = keys;
+        self.config.disabled = disabled;
+        self.extensions = extensions;
+        self.bind = bind;
+        defaults.extend(
+            self.extensions
+                .iter()
+                .map(|e| (e.name(), e.key(), e.mutates())),
+        );
+        for bind in &self.bind {
+            let template = Template::parse(bind)?;
+            defaults.push((template.name(), template.key(), template.mutates()));
+        }
+
+        let known = |name: &str| defaults.iter().any(|(n, _, _)| n == name);
+        if let Some(name) = self.config.keys.keys().find(|name| !known(name)) {
+            return Err(format!("No binding named {} to remap", name));
+        }
+        if let Some(name) = self
+            .config
+            .disabled
+            .iter()
+            .find(|name| *name != "mutating" && !known(name))
+        {
+            return Err(format!("No binding named {} to disable", name));
+        }
+        if let Some(name) = self.config.overrides.keys().find(|name| !known(name)) {
+            return Err(format!("No binding named {} to override", name));
+        }
+
+        // remapped and script keys can clash with the picker, with each other
+        // or with a binding left on its default key
+        let mut bound: HashMap<String, String> = HashMap::new();
+        for (name, default_key, mutates) in defaults {
+            let key = self.config.keys.get(&name).cloned().unwrap_or(default_key);
+            if (key.is_empty() && name != "names") || self.disabled(&name, mutates) {
+                continue;
+            }
+            let picker_key = PICKER_KEYS.iter().any(|(_, k, _)| *k == key);
+            if RESERVED_KEYS.contains(&key.as_str()) || picker_key {
+                return Err(format!(
+                    "{} can't be bound to {}, it's used by the picker",
+                    name, key
+                ));
+            }
+            if let Some(other) = bound.insert(key.clone(), name.clone()) {
+                return Err(format!("{} and {} are both bound to {}", other, name, key));
+            }
+        }
+
+        Ok(())
+    }
+
+    // what to put between names when accepting
+    fn names_delimiter(&self) -> String {
+        if self.print0 {
+            return "\0".into();
+        }
+        self.delimiter.clone().unwrap_or_else(|| "\n".into())
+    }
+
+    // the query the json binding applies to each item, if any
+    fn json_query(&self) -> Option<JsonQuery> {
+        match (&self.jsonpath, &self.jq) {
+            (Some(template), _) => Some(JsonQuery::JsonPath(template.clone())),
+            (None, Some(filter)) => Some(JsonQuery::Jq(filter.clone())),
+            (None, None) => None,
+        }
+    }
+
+    // full screen wins over any height, otherwise the flag wins over the config
+    fn height(&self) -> String {
+        if self.full_screen || self.config.full_screen {
+            return "100%".into();
+        }
+        self.height
+            .clone()
+            .or_else(|| self.config.height.clone())
+            .unwrap_or_else(|| "30%".into())
+    }
+
+    // the flag wins over the config
+    fn preview_window(&self) -> String {
+        self.preview_window
+            .clone()
+            .or_else(|| self.config.preview_window.clone())
+            .unwrap_or_else(|| "right:20%".into())
+    }
+
+    // flags every kubectl command should be run with, in a context other than the current one if given
+    fn global_args(&self, context: Option<&str>) -> Vec<String> {
+        let mut args = Vec::new();
+
+        if let Some(context) = context.or_else(|| self.context.as_deref()) {
+            args.push("--context".into());
+            args.push(context.into());
+        }
+
+        if let Some(kubeconfig) = &self.kubeconfig {
+            args.push("--kubeconfig".into());
+            args.push(kubeconfig.to_string_lossy().into_owned());
+        }
+        if let Some(cluster) = &self.cluster {
+            args.push("--cluster".into());
+            args.push(cluster.clone());
+        }
+        if let Some(user) = &self.user {
+            args.push("--user".into());
+            args.push(user.clone());
+        }
+
+        // service accounts are impersonated by their full user name
+        // system:serviceaccount:<namespace>:<name>
+        if let Some(sa) = &self.as_readonly_sa {
+            let user = if sa.contains(':') {
+                format!("system:serviceaccount:{}", sa)
+            } else {
+                format!(
+                    "system:serviceaccount:{}:{}",
+                    self.namespace.as_deref().unwrap_or("default"),
+                    sa
+                )
+            };
+            args.push("--as".into());
+            args.push(user);
+        }
+        if let Some(user) = &self.as_user {
+            args.push("--as".into());
+            args.push(user.clone());
+        }
+        for group in &self.as_group {
+            args.push("--as-group".into());
+            args.push(group.clone());
+        }
+
+        args
+    }
+
+    // applies the profile from --profile or $KUBECTL_SELECT_PROFILE to the options, where the
+    // command line doesn't already say otherwise
+    fn use_profile(&mut self) -> Result<(), String> {
+        let name = match self.profile.clone().or_else(|| {
+            std::env::var("KUBECTL_SELECT_PROFILE")
+                .ok()
+                .filter(|p| !p.is_empty())
+        }) {
+            Some(name) => name,
+            None => return Ok(()),
+        };
+
+        let profile = self.config.take_profile(&name)?;
+        self.context = self.context.take().or(profile.context);
+        self.namespace = self.namespace.take().or(profile.namespace);
+        self.resource_arg = self.resource_arg.take().or(profile.resource);
+        Ok(())
     }
 
     // run the end to end flow with the current options
-    fn run(&mut self) -> Option<String> {
-        if BindingContext::accepts_pods().contains(&self.resource) {
-            self.wide = true;
+    fn run(&mut self) -> Option<Outcome> {
+        if self.batch() {
+            return self.run_batch();
         }
 
+        // put all the items in a channel for skim to read from
+        let (tx_item, rx_item): (SkimItemSender, SkimItemReceiver) = unbounded();
+
         // everything builds from a kubectl get <resource> list
         // presented in the same format as kubectl would by through skim for fuzzy search
-        let kubectl_output = self.kubectl_get()?;
+        // events are the exception where a watch keeps streaming new rows in until skim exits
+        // with a status line the column header goes in as the first item, which skim keeps above the
+        // rest as a header line of its own
+        let (header, watch, kept) = if self.watches_events() {
+            let (header, watch) = self.kubectl_watch(tx_item)?;
+            (self.status(None).unwrap_or(header), Some(watch), Vec::new())
+        } else {
+            let kubectl_output = self.kubectl_get()?;
+
+            // nothing to pick from, unless this was navigated to and the user might want to go back
+            if kubectl_output.items.is_empty() && self.back.is_empty() {
+                return Some(Outcome::NoMatch);
+            }
+
+            // skip the picker when the query already narrows things down to a single item
+            if self.select_1 {
+                let matches =
+                    self.filter_items(kubectl_output.items.clone(), &self.query.join(" "));
+                if matches.len() == 1 {
+                    return self.handle_output(&self.accept_key(), &matches);
+                }
+            }
+
+            let counts = (kubectl_output.items.len(), kubectl_output.total);
+            let status = self.status(Some(counts));
+            if status.is_some() {
+                let _ = tx_item.send(Arc::new(kubectl_output.header.clone()));
+            }
+
+            // the items still selected from the last visit go first, as skim can't select them itself
+            let (kept, rest): (Vec<_>, Vec<_>) = kubectl_output
+                .items
+                .into_iter()
+                .partition(|i| self.selected.contains(&self.selection_key(&i.text())));
+            let kept: Vec<Arc<dyn SkimItem>> = kept
+                .into_iter()
+                .map(|mut i| {
+                    i.keep();
+                    Arc::new(i) as Arc<dyn SkimItem>
+                })
+                .collect();
+            for item in &kept {
+                let _ = tx_item.send(item.clone());
+            }
+            for item in rest {
+                let _ = tx_item.send(Arc::new(item));
+            }
+
+            // so that skim could know when to stop waiting for more items.
+            // we do this sync since kubectl buffers until everything is fetched anyway
+            drop(tx_item);
+            (status.unwrap_or(kubectl_output.header), None, kept)
+        };
 
         let prompt = format!("{} ⎈  ", self.resource);
 
+        let colors = theme::skim_colors();
+        let preview_window = self.preview_window();
+        let height = self.height();
+        let mut picker_binds: Vec<String> = PICKER_KEYS
+            .iter()
+            .map(|(_, key, action)| format!("{}:{}", key, action))
+            .collect();
+
+        // skim only gives the selection as listed, and the item under the cursor when nothing is
+        // selected, so for the order it was made in and what was really selected every toggle is
+        // logged as it happens
+        // selecting, deselecting or inverting everything is logged as an empty line
+        let toggles = self.toggle_log();
+        if let Some(toggles) = &toggles {
+            let _ = fs::remove_file(toggles);
+            let log = format!(
+                "execute-silent(printf '%s\\n' {{}} >> '{}')",
+                toggles.display()
+            );
+            picker_binds.push(format!("tab:{}+toggle+down", log));
+            picker_binds.push(format!("btab:{}+toggle+up", log));
+            for (_, key, action) in PICKER_KEYS.iter().filter(|(_, _, a)| a.ends_with("-all")) {
+                picker_binds.push(format!(
+                    "{}:execute-silent(echo >> '{}')+{}",
+                    key,
+                    toggles.display(),
+                    action
+                ));
+            }
+        }
         let mut options_builder = SkimOptionsBuilder::default();
         options_builder
-            .height(Some("30%"))
+            .height(Some(&height))
+            .color(Some(&colors))
             .multi(true)
+            .exact(self.exact)
+            .regex(self.regex)
+            .case(self.case_matching())
             .reverse(true)
             .prompt(Some(&prompt))
             .preview(Some(""))
-            .preview_window(Some("right:20%"))
-            .header(Some(&*kubectl_output.header))
-            .bind(vec!["ctrl-p:toggle-preview"])
+            .preview_window(Some(&preview_window))
+            .header(Some(&*header))
+            .header_lines(if self.no_status { 0 } else { 1 })
+            .bind(picker_binds.iter().map(String::as_str).collect())
             .expect(Some(
                 self.bindings
                     .lock()
@@ -80,6 +882,11 @@ impl Opts {
                     .join(","),
             ));
 
+        // matches that score the same keep the order they were listed in, the usual items first
+        if self.ranks_frecent() {
+            options_builder.tiebreak(Some("score,index".to_string()));
+        }
+
         let query_string = self.query.join(" ");
         if !self.query.is_empty() {
             options_builder.query(Some(&query_string));
@@ -87,115 +894,1768 @@ impl Opts {
 
         let options = options_builder.build().unwrap();
 
-        // put all the items in a channel for skim to read from
-        let (tx_item, rx_item): (SkimItemSender, SkimItemReceiver) = unbounded();
-        for item in kubectl_output.items {
-            let _ = tx_item.send(Arc::new(item));
+        // run skim, get the selected items and the key used to terminate skim
+        let full_screen = self.full_screen || self.config.full_screen;
+        if full_screen {
+            picker::alternate_screen(true);
+        }
+        let (selected_items, key, query) = Skim::run_with(&options, Some(rx_item))
+            .map(|out| (out.selected_items, out.accept_key, out.query))
+            .unwrap_or_else(|| (Vec::new(), None, String::new()));
+        if full_screen {
+            picker::alternate_screen(false);
         }
 
-        // so that skim could know when to stop waiting for more items.
-        // we do this sync since kubectl buffers until everything is fetched anyway
-        drop(tx_item);
+        // remember what was typed so coming back to this listing restores it
+        self.query = query.split_whitespace().map(String::from).collect();
 
-        // run skim, get the selected items and the key used to terminate skim
-        let (selected_items, key) = Skim::run_with(&options, Some(rx_item))
-            .map(|out| (out.selected_items, out.accept_key))
-            .unwrap_or_else(|| (Vec::new(), None));
+        if let Some(watch) = watch {
+            supervisor::release(watch);
+        }
 
-        // anything returned will be printed to stdout
-        key.map(|k| self.handle_output(&k, &selected_items))
-            .flatten()
-    }
+        let logged = match &toggles {
+            Some(toggles) => {
+                let logged = fs::read_to_string(toggles).unwrap_or_default();
+                let _ = fs::remove_file(toggles);
+                logged
+            }
+            None => String::new(),
+        };
 
-    // handles any action such as key binding / exit / accept and returns the output of the action
-    fn handle_output(&self, key: &str, selected_items: &[Arc<dyn SkimItem>]) -> Option<String> {
-        let items: Vec<String> = selected_items
+        // remember what was selected so coming back to this listing selects it again
+        let chosen = chosen_items(kept, &selected_items, &logged);
+        self.selected = chosen
             .iter()
-            .map(|i| i.output().into_owned())
+            .map(|i| self.selection_key(&i.output()))
             .collect();
+        let selected_items = if chosen.is_empty() {
+            selected_items
+        } else {
+            chosen
+        };
+        let selected_items = if self.order == "selected" {
+            in_selection_order(selected_items, &logged)
+        } else {
+            selected_items
+        };
 
-        let columns: Vec<Vec<String>> = items
-            .iter()
-            .map(|l| l.split_whitespace().map(String::from).collect())
-            .collect();
+        // anything returned will be printed to stdout
+        match key {
+            Some(k) => {
+                let k = if k.is_empty() { self.accept_key() } else { k };
+                self.handle_output(&k, &selected_items)
+            }
+            None => Some(Outcome::Aborted),
+        }
+    }
 
-        // pre calculate all the names of the selected items since we only really need the name key for most cases
-        let names: Vec<String> = columns
-            .iter()
-            .filter_map(|c| c.first().map(String::from))
-            .collect();
+    // where the picker logs each item toggled with tab
+    fn toggle_log(&self) -> Option<PathBuf> {
+        Some(supervisor::temp_dir()?.join("toggles"))
+    }
 
-        let binding_context = BindingContext {
-            namespace: self.namespace.clone(),
-            resource: self.resource.clone(),
-            names,
-            columns,
-        };
+    // what a row is known by when it's selected again, its context, namespace and name
+    fn selection_key(&self, row: &str) -> String {
+        split_leading_columns(&self.header, row, self.prefix_columns().len() + 1).join(" ")
+    }
 
-        // run our binding if it exists and can run this resource type, otherwise
-        let bindings = self.bindings.lock().unwrap();
-        let binding = bindings.get(key)?;
+    // scripts skip skim entirely with --filter, or --action unless it's waiting on --select-1
+    fn batch(&self) -> bool {
+        self.filter.is_some() || (self.action.is_some() && !self.select_1)
+    }
 
-        if !binding.runs_for(&self.resource) {
-            return Some(format!(
-                "{} does not work for resource type {}",
-                binding.description(),
-                self.resource
-            ));
-        }
-        binding.run(&binding_context)
+    // looks up a binding by its name rather than its key
+    fn binding_named(&self, name: &str) -> Option<Arc<dyn Binding + Send + Sync>> {
+        self.bindings
+            .lock()
+            .unwrap()
+            .values()
+            .find(|b| b.name() == name)
+            .cloned()
     }
 
-    // kubectl get with options for the resource specified in the arguments
-    // kubectl get -n <namspace>? <resource>
-    // todo: add ability to change args based on resource with custom-columns
-    // for example: pods might want to always add the node and ip name without full -o
-    fn kubectl_get(&mut self) -> Option<KubectlOutput> {
-        let mut builder = kubectl_base_cmd(self.namespace.as_deref(), "get", self.resource.clone());
-        if self.wide {
-            builder = builder.arg("--output").arg("wide");
-        }
+    // the key of the binding that accepting runs, which is --action when given and names otherwise
+    fn accept_key(&self) -> String {
+        self.action
+            .as_deref()
+            .and_then(|action| self.binding_named(action))
+            .map(|b| b.key())
+            .unwrap_or_default()
+    }
 
-        let lines: Vec<String> = builder
-            .capture()
-            .ok()?
-            .stdout_str()
-            .lines()
-            .map(String::from)
-            .collect();
+    // runs the --action binding on every item matching --filter
+    fn run_batch(&mut self) -> Option<Outcome> {
+        let kubectl_output = self.kubectl_get()?;
 
-        // fill our function key bindings based on the number of columns
-        // 19 is the number of function keys on my full sized keyboard as a sane default
-        let header = lines.first()?;
-        let header_columns: Vec<String> = header.split_whitespace().map(String::from).collect();
-        let max_columns = header_columns.len().min(19);
+        let action = self.action.clone().unwrap_or_else(|| "names".into());
+        let binding = match self.binding_named(&action) {
+            Some(binding) => binding,
+            None => return Some(Outcome::Failed(format!("No binding named {}", action))),
+        };
 
-        for (i, name) in header_columns.iter().skip(1).take(max_columns).enumerate() {
-            self.add_binding(Column::new(name.clone(), i + 1));
+        if binding.mutates() && !self.yes && !self.dry_run {
+            return Some(Outcome::Failed(format!(
+                "{} changes the selected items, pass --yes to run it in batch mode",
+                binding.description()
+            )));
         }
 
-        let out = KubectlOutput {
-            header: header.into(),
-            items: lines
-                .iter()
-                .skip(1)
-                .cloned()
-                .map(|i| KubectlItem::new(i, self.resource.clone(), self.bindings.clone()))
-                .collect(),
+        let filter = self.filter.clone().unwrap_or_default();
+        let items = self.filter_items(kubectl_output.items, &filter);
+
+        self.handle_output(&binding.key(), &items)
+    }
+
+    // switch over to the listing a binding navigated to
+    // the current listing is kept so back can return to it
+    fn navigate(&mut self, navigation: Navigation) {
+        let navigation = match navigation.history {
+            Some(History::Back) => match self.back.pop() {
+                Some(previous) => {
+                    self.forward.push(self.location());
+                    previous
+                }
+                None => return,
+            },
+            // sorting stays on the same listing so there's nothing to go back to
+            Some(History::Sort(sort_by)) => {
+                self.sort_by = Some(sort_by);
+                return;
+            }
+            Some(History::Restarting) => {
+                self.restarting = !self.restarting;
+                return;
+            }
+            Some(History::Wide) => {
+                self.wide_toggled = Some(!self.lists_wide());
+                return;
+            }
+            Some(History::Labels) => {
+                self.show_labels = !self.show_labels;
+                return;
+            }
+            Some(History::Scroll(by)) => {
+                self.scroll = (self.scroll as isize + by).max(0) as usize;
+                return;
+            }
+            Some(History::Stay) => return,
+            // selections from other contexts would be listed from the wrong cluster
+            Some(History::Recent) => {
+                let selections: Vec<recent::Selection> = recent::load()
+                    .into_iter()
+                    .filter(|s| s.context == self.context)
+                    .collect();
+                match recent::pick(&selections) {
+                    Some(selection) => {
+                        self.back.push(self.location());
+                        self.forward.clear();
+                        selection.navigation()
+                    }
+                    None => return,
+                }
+            }
+            Some(History::Bookmark) => {
+                let bookmark = bookmarks::Bookmark {
+                    context: self.context.clone(),
+                    namespace: navigation.namespace,
+                    resource: navigation.resource,
+                    names: navigation.names,
+                    selector: self.selector.clone(),
+                    query: self.query.clone(),
+                };
+                if let Err(e) = bookmarks::add(bookmark) {
+                    eprintln!("{}", e);
+                }
+                return;
+            }
+            Some(History::Bookmarks) => {
+                let bookmarks: Vec<bookmarks::Bookmark> = bookmarks::load()
+                    .into_iter()
+                    .filter(|b| b.context == self.context)
+                    .collect();
+                match bookmarks::pick(&bookmarks) {
+                    Some(bookmark) => {
+                        self.back.push(self.location());
+                        self.forward.clear();
+                        bookmark.navigation()
+                    }
+                    None => return,
+                }
+            }
+            Some(History::Forward) => match self.forward.pop() {
+                Some(next) => {
+                    self.back.push(self.location());
+                    next
+                }
+                None => return,
+            },
+            None => {
+                self.back.push(self.location());
+                self.forward.clear();
+                navigation
+            }
         };
 
-        Some(out)
+        self.wide_toggled = None;
+        self.scroll = 0;
+        self.resource = navigation.resource;
+        self.namespace = navigation.namespace;
+        self.all_namespaces = navigation.all_namespaces;
+        self.names = navigation.names;
+        self.selector = navigation.selector;
+        self.field_selector = navigation.field_selector;
+        self.query = navigation.query;
+        self.get_args = navigation.get_args;
+        self.selected = navigation.selected;
     }
-}
 
-fn main() {
-    let mut opts: Opts = Opts::parse();
-    opts.setup_bindings();
+    // runs binding output through the --pipe command when there is one
+    fn pipe_output(&self, output: String) -> String {
+        let pipe = match &self.pipe {
+            Some(pipe) if !output.is_empty() => pipe,
+            _ => return output,
+        };
+
+        match supervisor::capture(Exec::shell(pipe), Some(&output), None) {
+            Some(piped) => {
+                eprint!("{}", piped.stderr_str());
+                piped.stdout_str()
+            }
+            None => output,
+        }
+    }
+
+    // the current listing as somewhere to navigate back to
+    fn location(&self) -> Navigation {
+        Navigation {
+            resource: self.resource.clone(),
+            namespace: self.namespace.clone(),
+            all_namespaces: self.all_namespaces,
+            names: self.names.clone(),
+            selector: self.selector.clone(),
+            field_selector: self.field_selector.clone(),
+            query: self.query.clone(),
+            get_args: self.get_args.clone(),
+            selected: self.selected.clone(),
+            history: None,
+        }
+    }
+
+    // counts the selection towards --frecent and keeps it for --history
+    fn remember(&self, context: Option<String>, binding_context: &BindingContext) {
+        frecency::record(
+            context.as_deref(),
+            binding_context.namespace.as_deref(),
+            &self.resource,
+            &binding_context.names,
+        );
+        recent::record(recent::Selection {
+            context,
+            namespace: binding_context.namespace.clone(),
+            resource: self.resource.clone(),
+            names: binding_context.names.clone(),
+        });
+    }
+
+    fn case_matching(&self) -> CaseMatching {
+        match self.case.as_str() {
+            "sensitive" => CaseMatching::Respect,
+            "insensitive" => CaseMatching::Ignore,
+            _ => CaseMatching::Smart,
+        }
+    }
+
+    // whether a term is matched ignoring case, which with smart case is when it has no capitals
+    fn ignores_case(&self, term: &str) -> bool {
+        match self.case_matching() {
+            CaseMatching::Respect => false,
+            CaseMatching::Ignore => true,
+            CaseMatching::Smart => !term.chars().any(char::is_uppercase),
+        }
+    }
+
+    // the items matching a query the way the picker would
+    // the query is a space separated list of terms that must all match the row, fuzzily or exactly,
+    // or with --regex a single regex where an invalid one matches nothing
+    fn filter_items(&self, items: Vec<KubectlItem>, query: &str) -> Vec<Arc<dyn SkimItem>> {
+        let matcher = match self.case_matching() {
+            CaseMatching::Respect => SkimMatcherV2::default().respect_case(),
+            CaseMatching::Ignore => SkimMatcherV2::default().ignore_case(),
+            CaseMatching::Smart => SkimMatcherV2::default().smart_case(),
+        };
+        let regex = if self.regex {
+            match RegexBuilder::new(query)
+                .case_insensitive(self.ignores_case(query))
+                .build()
+            {
+                Ok(regex) => Some(regex),
+                Err(_) => return Vec::new(),
+            }
+        } else {
+            None
+        };
+
+        let matches = |text: &str, term: &str| {
+            if !self.exact {
+                matcher.fuzzy_match(text, term).is_some()
+            } else if self.ignores_case(term) {
+                text.to_lowercase().contains(&term.to_lowercase())
+            } else {
+                text.contains(term)
+            }
+        };
+
+        items
+            .into_iter()
+            .filter(|i| match &regex {
+                Some(regex) => regex.is_match(&i.text()),
+                None => query
+                    .split_whitespace()
+                    .all(|term| matches(&i.text(), term)),
+            })
+            .map(|i| Arc::new(i) as Arc<dyn SkimItem>)
+            .collect()
+    }
+
+    // starts on a listing from before rather than the one on the command line
+    // a context of none is whichever one kubectl is on
+    fn start_at(&mut self, context: Option<String>, navigation: Navigation) {
+        if context.is_some() {
+            self.context = context;
+        }
+        self.resource = navigation.resource;
+        self.namespace = navigation.namespace;
+        self.all_namespaces = navigation.all_namespaces;
+        self.names = navigation.names;
+        self.selector = navigation.selector;
+        self.field_selector = navigation.field_selector;
+        self.query = navigation.query;
+        self.get_args = navigation.get_args;
+        self.selected = navigation.selected;
+    }
+
+    // the current listing for --resume to reopen
+    fn session(&self) -> session::Session {
+        let location = self.location();
+        session::Session {
+            context: self.context.clone(),
+            namespace: location.namespace,
+            all_namespaces: location.all_namespaces,
+            resource: location.resource,
+            names: location.names,
+            selector: location.selector,
+            field_selector: location.field_selector,
+            query: location.query,
+            get_args: location.get_args,
+            selected: location.selected,
+        }
+    }
+
+    // handles any action such as key binding / exit / accept and returns the output of the action
+    fn handle_output(&self, key: &str, selected_items: &[Arc<dyn SkimItem>]) -> Option<Outcome> {
+        // run our binding if it exists and can run this resource type, otherwise
+        let bindings = self.bindings.lock().unwrap();
+        let binding = bindings.get(key)?;
+
+        if !binding.runs_for(&self.resource) {
+            debug!(binding = %binding.name(), resource = %self.resource, "binding does not run for resource");
+            return Some(Outcome::Failed(format!(
+                "{} does not work for resource type {}",
+                binding.description(),
+                self.resource
+            )));
+        }
+
+        // bindings that only want names don't pay for splitting every cell of the rows
+        let leading = if binding.needs_columns() {
+            usize::MAX
+        } else {
+            self.prefix_columns().len() + 1
+        };
+        let rows: Vec<Vec<String>> = selected_items
+            .iter()
+            .map(|i| split_leading_columns(&self.header, &i.output(), leading))
+            .collect();
+        // counted across every context and namespace the rows are from
+        let limit = [
+            binding.max_items(),
+            self.max_select.or(self.config.max_select),
+        ]
+        .iter()
+        .flatten()
+        .min()
+        .copied();
+        if let Some(limit) = limit.filter(|&limit| rows.len() > limit) {
+            return Some(Outcome::Failed(format!(
+                "{} runs on at most {} items at once but {} were selected",
+                binding.description(),
+                limit,
+                rows.len()
+            )));
+        }
+        let confirm = self.confirmation(binding.as_ref());
+        if confirm != Confirm::Never && !self.dry_run {
+            let items = self.confirmation_items(binding, &rows);
+            let mut warning = format!("{} will run on {} items", binding.description(), rows.len());
+            if let Some(caution) = binding.warning() {
+                warning = format!("{}, {}", warning, caution);
+            }
+            if !picker::confirm(&warning, &items, confirm == Confirm::Typed) {
+                return Some(Outcome::Failed(format!(
+                    "{} cancelled",
+                    binding.description()
+                )));
+            }
+        }
+        if self.prefix_columns().is_empty() {
+            return self.run_binding(binding, self.context.clone(), self.namespace.clone(), &rows);
+        }
+
+        let groups = self.group_rows(rows);
+
+        let mut output = String::new();
+        let mut structured = Vec::new();
+        let mut errors = String::new();
+        let mut failed = false;
+        for (context, namespace, rows) in &groups {
+            set_global_args(self.global_args(context.as_deref()));
+            let outcome = self.run_binding(
+                binding,
+                context.clone().or_else(|| self.context.clone()),
+                namespace.clone(),
+                rows,
+            );
+            set_global_args(self.global_args(None));
+
+            let label: Vec<&str> = [context, namespace]
+                .iter()
+                .filter_map(|l| l.as_deref())
+                .collect();
+            let o = match outcome {
+                Some(Outcome::Output(o)) | Some(Outcome::Structured(o)) => o,
+                Some(Outcome::Errored(o, e)) => {
+                    errors.push_str(&e);
+                    failed = true;
+                    o
+                }
+                // navigating, failing or having nothing to do ends it for the other groups too
+                other => return other,
+            };
+            if binding.structured_output() {
+                if !o.is_empty() {
+                    structured.push(o);
+                }
+            } else if groups.len() > 1 && !o.is_empty() {
+                output.push_str(&format!("# {}\n{}", label.join(" "), o));
+            } else {
+                output.push_str(&o);
+            }
+        }
+        if binding.structured_output() {
+            output = self.join_structured(structured);
+        }
+        if failed {
+            Some(Outcome::Errored(output, errors))
+        } else if binding.structured_output() {
+            Some(Outcome::Structured(output))
+        } else {
+            Some(Outcome::Output(output))
+        }
+    }
+
+    // the structured output of each context and namespace as one, so -o json is still a single array
+    fn join_structured(&self, outputs: Vec<String>) -> String {
+        if self.output != "json" {
+            return outputs.join(&self.names_delimiter());
+        }
+        let items: Vec<Value> = outputs
+            .iter()
+            .filter_map(|o| serde_json::from_str::<Vec<Value>>(o).ok())
+            .flatten()
+            .collect();
+        serde_json::to_string_pretty(&items)
+            .map(|json| json + "\n")
+            .unwrap_or_default()
+    }
+
+    // runs the binding that reverses the last one in the audit log, in the context it ran in
+    // returns the exit code
+    fn undo(&self) -> i32 {
+        let action = match audit::last_action() {
+            Ok(action) => action,
+            Err(e) => {
+                eprintln!("{}", e);
+                return EXIT_FAILED;
+            }
+        };
+        let binding = match inverse(&action.binding) {
+            Some(binding) => binding,
+            None => {
+                eprintln!("The last action, {}, can't be undone", action.binding);
+                return EXIT_FAILED;
+            }
+        };
+
+        let warning = format!(
+            "undoing {} in {} with {}",
+            action.binding,
+            action.context,
+            binding.name()
+        );
+        if !self.dry_run && !picker::confirm(&warning, &action.names, false) {
+            return EXIT_ABORTED;
+        }
+
+        set_global_args(self.global_args(Some(&action.context)));
+        let binding_context = BindingContext {
+            context: Some(action.context),
+            namespace: Some(action.namespace),
+            resource: action.resource,
+            names: action.names,
+            columns: Vec::new(),
+            header: Vec::new(),
+        };
+        // recorded like any other run so undoing again puts it back
+        audit::begin(
+            &binding.name(),
+            binding_context.namespace.as_deref(),
+            &binding_context.resource,
+            &binding_context.names,
+        );
+        kubectl::take_failures();
+        let output = binding.run(&binding_context).unwrap_or_default();
+        let failures = kubectl::take_failures();
+        audit::end();
+        if !self.dry_run {
+            if let Err(e) = cache::clear() {
+                debug!(error = %e, "failed to clear the cache");
+            }
+        }
+
+        print!("{}", output);
+        if failures.is_empty() {
+            0
+        } else {
+            eprint!("{}", failures.concat());
+            EXIT_FAILED
+        }
+    }
+
+    // how to ask before running the binding, set in the config or else as the binding asks
+    fn confirmation(&self, binding: &dyn Binding) -> Confirm {
+        match self.config.confirm.get(&binding.name()) {
+            Some(confirm) => *confirm,
+            None => binding.requires_confirmation(),
+        }
+    }
+
+    // each item runs in its own context and namespace, with those columns taken off its row
+    fn group_rows(&self, rows: Vec<Vec<String>>) -> Vec<RowGroup> {
+        let mut groups: Vec<RowGroup> = Vec::new();
+        for mut prefix in rows {
+            let row = prefix.split_off(self.prefix_columns().len().min(prefix.len()));
+            let namespace = if self.multi_namespace() || self.lists_all_namespaces() {
+                prefix.pop()
+            } else {
+                self.namespace.clone()
+            };
+            let context = prefix.pop();
+            match groups
+                .iter_mut()
+                .find(|(c, n, _)| *c == context && *n == namespace)
+            {
+                Some((_, _, rows)) => rows.push(row),
+                None => groups.push((context, namespace, vec![row])),
+            }
+        }
+        groups
+    }
+
+    // the items to list when asking before running a binding, after the context and namespace
+    // columns that say where each is from
+    // bindings that describe the items are asked about each context and namespace in turn
+    fn confirmation_items(
+        &self,
+        binding: &Arc<dyn Binding + Send + Sync>,
+        rows: &[Vec<String>],
+    ) -> Vec<String> {
+        let listed: Vec<String> = rows
+            .iter()
+            .map(|r| r[..r.len().min(self.prefix_columns().len() + 1)].join(" "))
+            .collect();
+        if self.prefix_columns().is_empty() {
+            let context =
+                self.binding_context(binding, self.context.clone(), self.namespace.clone(), rows);
+            return binding.describe_items(&context).unwrap_or(listed);
+        }
+
+        let mut described = Vec::new();
+        for (context, namespace, rows) in self.group_rows(rows.to_vec()) {
+            let label: Vec<&str> = [&context, &namespace]
+                .iter()
+                .filter_map(|l| l.as_deref())
+                .collect();
+            let label = label.join(" ");
+            set_global_args(self.global_args(context.as_deref()));
+            let items = binding.describe_items(&self.binding_context(
+                binding,
+                context.clone().or_else(|| self.context.clone()),
+                namespace.clone(),
+                &rows,
+            ));
+            set_global_args(self.global_args(None));
+            match items {
+                Some(items) => described.extend(items.iter().map(|i| format!("{} {}", label, i))),
+                None => return listed,
+            }
+        }
+        described
+    }
+
+    // what a binding is given about the selected rows of one context and namespace
+    fn binding_context(
+        &self,
+        binding: &Arc<dyn Binding + Send + Sync>,
+        context: Option<String>,
+        namespace: Option<String>,
+        rows: &[Vec<String>],
+    ) -> BindingContext {
+        // pre calculate all the names of the selected items since we only really need the name key for most cases
+        // the columns are only copied for bindings that ask for them
+        let names: Vec<String> = rows.iter().filter_map(|r| r.first().cloned()).collect();
+        let columns = if binding.needs_columns() {
+            rows.to_vec()
+        } else {
+            Vec::new()
+        };
+
+        BindingContext {
+            context,
+            namespace,
+            resource: self.resource.clone(),
+            names,
+            columns,
+            header: header_columns(&self.header)
+                .into_iter()
+                .skip(self.prefix_columns().len())
+                .collect(),
+        }
+    }
+
+    // runs a binding on the selected rows in the current context
+    fn run_binding(
+        &self,
+        binding: &Arc<dyn Binding + Send + Sync>,
+        context: Option<String>,
+        namespace: Option<String>,
+        rows: &[Vec<String>],
+    ) -> Option<Outcome> {
+        let binding_context = self.binding_context(binding, context.clone(), namespace, rows);
+
+        // the cluster should reject writes anyway but don't even try in read only mode
+        if self.as_readonly_sa.is_some() && binding.mutates() {
+            return Some(Outcome::Failed(format!(
+                "{} is disabled in read only mode",
+                binding.description()
+            )));
+        }
+
+        if let Some(navigation) = binding.navigate(&binding_context) {
+            info!(binding = %binding.name(), resource = %navigation.resource, "navigating");
+            return Some(Outcome::Navigate(navigation));
+        }
+
+        if binding_context.names.is_empty() {
+            debug!(binding = %binding.name(), "no names in the selected items");
+            return Some(Outcome::NoMatch);
+        }
+
+        // rows from stdin may not be from the cluster at all
+        if self.table.is_none() {
+            self.remember(context, &binding_context);
+        }
+
+        // everything a mutating binding runs goes in the audit log
+        if binding.mutates() {
+            audit::begin(
+                &binding.name(),
+                binding_context.namespace.as_deref(),
+                &binding_context.resource,
+                &binding_context.names,
+            );
+        }
+        // bindings without output still count as having run so loop mode carries on
+        info!(binding = %binding.name(), items = binding_context.names.len(), "running binding");
+        let started = Instant::now();
+        kubectl::take_failures();
+        let output = binding.run(&binding_context).unwrap_or_default();
+        let failures = kubectl::take_failures();
+        audit::end();
+        // what was cached no longer reflects the cluster
+        if binding.mutates() && !self.dry_run {
+            if let Err(e) = cache::clear() {
+                debug!(error = %e, "failed to clear the cache");
+            }
+        }
+        info!(
+            binding = %binding.name(),
+            elapsed = ?started.elapsed(),
+            output_bytes = output.len(),
+            "binding finished"
+        );
+
+        // follow up mutating actions with the new state of what was changed
+        let summary = if binding.mutates() && !self.dry_run && !self.quiet {
+            summary::summarize(&binding_context)
+        } else {
+            None
+        };
+        let output = match summary {
+            Some(summary) if output.trim().is_empty() => format!("{}\n", summary),
+            Some(summary) => format!("{}\n{}\n", output.trim_end(), summary),
+            None => output,
+        };
+
+        if failures.is_empty() && binding.structured_output() {
+            Some(Outcome::Structured(output))
+        } else if failures.is_empty() {
+            Some(Outcome::Output(output))
+        } else {
+            warn!(binding = %binding.name(), failures = failures.len(), "kubectl failed in binding");
+            Some(Outcome::Errored(output, failures.concat()))
+        }
+    }
+
+    // whether rows come from several contexts and start with the context they're from
+    // events are watched and helm releases listed from the current context only
+    fn multi_context(&self) -> bool {
+        !self.contexts.is_empty()
+            && !self.watches_events()
+            && !resources::is_one_of(&self.resource, &BindingContext::accepts_helm())
+    }
+
+    // whether rows come from several namespaces and start with the namespace they're from
+    fn multi_namespace(&self) -> bool {
+        !self.namespaces.is_empty()
+            && !self.watches_events()
+            && !resources::is_one_of(&self.resource, &BindingContext::accepts_helm())
+    }
+
+    // whether -A lists the resource, which kubectl gives a NAMESPACE column of its own
+    // helm releases are listed across namespaces already, and cluster wide resources have none
+    fn lists_all_namespaces(&self) -> bool {
+        self.all_namespaces
+            && self.table.is_none()
+            && !resources::is_one_of(&self.resource, &BindingContext::accepts_helm())
+            && resources::is_namespaced(&self.resource)
+    }
+
+    // the columns put in front of each row to say where it came from
+    fn prefix_columns(&self) -> Vec<&str> {
+        let mut columns = Vec::new();
+        if self.multi_context() {
+            columns.push("CONTEXT");
+        }
+        if self.multi_namespace() || self.lists_all_namespaces() {
+            columns.push("NAMESPACE");
+        }
+        columns
+    }
+
+    // the columns of the listing to show in the picker
+    fn view(&self) -> View {
+        View {
+            scroll: self.scroll,
+            width: self.row_width(),
+        }
+    }
+
+    // the room for a row in the picker with --fit, less skim's cursor and a preview window beside it
+    // example: right:20% of 200 columns leaves 157
+    fn row_width(&self) -> Option<usize> {
+        if !(self.fit || self.config.fit) {
+            return None;
+        }
+        let columns = picker::terminal_width()?;
+
+        let preview_window = self.preview_window();
+        let parts: Vec<&str> = preview_window.split(':').collect();
+        let stacked = parts
+            .iter()
+            .any(|p| matches!(*p, "up" | "down" | "top" | "bottom"));
+        let preview = if stacked || parts.contains(&"hidden") {
+            0
+        } else {
+            match parts
+                .iter()
+                .find(|p| p.chars().next().map_or(false, |c| c.is_ascii_digit()))
+            {
+                Some(size) => match size.strip_suffix('%') {
+                    Some(percent) => columns * percent.parse::<usize>().unwrap_or(50) / 100,
+                    None => size.parse().unwrap_or(columns / 2),
+                },
+                None => columns / 2,
+            }
+        };
+        Some(columns.saturating_sub(preview + 3))
+    }
+
+    // pods are listed wide for their node and ip unless toggled off in the picker
+    fn lists_wide(&self) -> bool {
+        self.wide_toggled.unwrap_or_else(|| {
+            self.wide
+                || self.resource_defaults().map_or(false, |d| d.wide)
+                || resources::is_one_of(&self.resource, &BindingContext::accepts_pods())
+        })
+    }
+
+    // the patterns piped on stdin when listing the resource they were piped for
+    fn piped_names(&self) -> Option<&[Regex]> {
+        match &self.piped {
+            Some((resource, patterns)) if *resource == self.resource => Some(patterns),
+            _ => None,
+        }
+    }
+
+    // rows from several contexts or namespaces don't start with the name so they're left as listed
+    fn ranks_frecent(&self) -> bool {
+        (self.frecent || self.config.frecent) && self.prefix_columns().is_empty()
+    }
+
+    // what the rows are sorted by, from --sort-by or the picker before the config's default for the resource
+    fn sort_by(&self) -> Option<&str> {
+        self.sort_by
+            .as_deref()
+            .or_else(|| self.resource_defaults()?.sort_by.as_deref())
+    }
+
+    // the config's defaults for the resource being listed, found by any of its names
+    fn resource_defaults(&self) -> Option<&ResourceDefaults> {
+        self.config
+            .resources
+            .iter()
+            .find(|(name, _)| resources::is_one_of(&self.resource, &[name.to_string()]))
+            .map(|(_, defaults)| defaults)
+    }
+
+    // the events resource runs as a firehose of everything happening in the namespace
+    fn watches_events(&self) -> bool {
+        self.table.is_none()
+            && resources::is_one_of(&self.resource, &BindingContext::accepts_events())
+    }
+
+    // kubectl get with options for the resource specified in the arguments
+    // kubectl get -n <namspace>? <resource>
+    fn kubectl_get_cmd(&self) -> subprocess::Exec {
+        self.kubectl_get_cmd_in(self.namespace.as_deref())
+    }
+
+    fn kubectl_get_cmd_in(&self, namespace: Option<&str>) -> subprocess::Exec {
+        // helm releases are listed by helm instead, across all namespaces unless one is given
+        if resources::is_one_of(&self.resource, &BindingContext::accepts_helm()) {
+            let builder = Exec::cmd("helm").arg("list").args(&helm_args());
+            return match namespace {
+                Some(namespace) => builder.arg("--namespace").arg(namespace),
+                None => builder.arg("--all-namespaces"),
+            };
+        }
+
+        let all_namespaces = self.lists_all_namespaces();
+        let namespace = if all_namespaces { None } else { namespace };
+
+        let mut builder = kubectl_base_cmd(namespace, "get", self.resource.clone());
+        if all_namespaces {
+            builder = builder.arg("--all-namespaces");
+        }
+        if self.renders_json() {
+            builder = builder.arg("--output").arg("json");
+        } else if let Some(columns) = self.custom_columns(all_namespaces) {
+            builder = builder
+                .arg("--output")
+                .arg(format!("custom-columns={}", columns));
+        } else if self.lists_wide() {
+            builder = builder.arg("--output").arg("wide");
+        }
+        // kubectl only adds labels to its own columns, custom columns can list .metadata.labels instead
+        // and a table laid out from json gets its own LABELS column
+        let own_columns = self.custom_columns(all_namespaces).is_none() && !self.renders_json();
+        if self.show_labels && own_columns {
+            builder = builder.arg("--show-labels");
+        }
+        if let Some(sort_by) = self.sort_by().filter(|s| is_jsonpath(s)) {
+            builder = builder.arg("--sort-by").arg(sort_by);
+        }
+        if let Some(selector) = &self.selector {
+            builder = builder.arg("--selector").arg(selector);
+        }
+        if let Some(field_selector) = &self.field_selector {
+            builder = builder.arg("--field-selector").arg(field_selector);
+        }
+        builder.args(&self.names).args(&self.get_args)
+    }
+
+    // the line above the column header saying how much was listed and what narrowed it down
+    // skim's info line counts what the query matches, and the preview how many are selected
+    // example: 4 of 12 pods | namespace default | selector app=api | restarting only
+    fn status(&self, counts: Option<(usize, usize)>) -> Option<String> {
+        if self.no_status {
+            return None;
+        }
+
+        let mut status = vec![match counts {
+            Some((listed, total)) if listed == total => format!("{} {}", total, self.resource),
+            Some((listed, total)) => format!("{} of {} {}", listed, total, self.resource),
+            None => self.resource.clone(),
+        }];
+        if self.multi_context() {
+            status.push(format!("contexts {}", self.contexts.join(",")));
+        }
+        if self.multi_namespace() {
+            status.push(format!("namespaces {}", self.namespaces.join(",")));
+        } else if self.lists_all_namespaces() {
+            status.push("all namespaces".into());
+        } else if let Some(namespace) = &self.namespace {
+            status.push(format!("namespace {}", namespace));
+        }
+        if !self.names.is_empty() {
+            status.push(format!("names {}", self.names.join(",")));
+        }
+        if let Some(selector) = &self.selector {
+            status.push(format!("selector {}", selector));
+        }
+        if let Some(field_selector) = &self.field_selector {
+            status.push(format!("field selector {}", field_selector));
+        }
+        if !self.get_args.is_empty() {
+            status.push(self.get_args.join(" "));
+        }
+        if self.restarting && header_columns(&self.header).iter().any(|c| c == "RESTARTS") {
+            status.push("restarting only".into());
+        }
+        if self.table.is_some() {
+            status.push("from stdin".into());
+        }
+        if self.piped_names().is_some() {
+            status.push("names from stdin".into());
+        }
+        if let Some(sort_by) = self.sort_by() {
+            status.push(format!("sorted by {}", sort_by));
+        }
+
+        Some(status.join(" | "))
+    }
+
+    // helm and watched events only come as text
+    fn renders_json(&self) -> bool {
+        (self.from_json || self.config.from_json)
+            && !resources::is_one_of(&self.resource, &BindingContext::accepts_helm())
+            && !self.watches_events()
+    }
+
+    // the lines of a listing, laid out from its json when it was fetched that way
+    fn listing(&self, stdout: &str) -> Option<Vec<String>> {
+        if !self.renders_json() {
+            return Some(stdout.lines().map(String::from).collect());
+        }
+        let items = json_items(stdout)?;
+        if items.is_empty() {
+            return Some(Vec::new());
+        }
+        Some(table::render(
+            &items,
+            &self.resource,
+            self.custom_columns(false).as_deref(),
+            self.lists_wide(),
+            self.show_labels,
+        ))
+    }
+
+    // the custom columns configured for the current resource
+    // bindings read the name from the first column, or the second when listing all namespaces,
+    // so those are put in front of whatever was configured
+    fn custom_columns(&self, all_namespaces: bool) -> Option<String> {
+        let configured = self.config.columns.get(&self.resource)?;
+        let mut columns = Vec::new();
+        if all_namespaces {
+            columns.push("NAMESPACE:.metadata.namespace");
+        }
+        if !configured.starts_with("NAME:") {
+            columns.push("NAME:.metadata.name");
+        }
+        columns.push(configured);
+        Some(columns.join(","))
+    }
+
+    // an empty list is still a success, only kubectl failing returns none
+    fn kubectl_get(&mut self) -> Option<KubectlOutput> {
+        let mut lines = if let Some(table) = &self.table {
+            table.clone()
+        } else if self.multi_context() || self.multi_namespace() {
+            self.kubectl_get_each()?
+        } else {
+            let captured = execute_cached(self.kubectl_get_cmd())?;
+            if !captured.exit_status.success() {
+                eprint!("{}", captured.stderr_str());
+                return None;
+            }
+            self.listing(&captured.stdout_str())?
+        };
+
+        let header = lines.first().cloned().unwrap_or_default();
+        self.header = header.clone();
+        self.add_column_bindings(&header);
+
+        // the total is of everything listed so the status says how much the piped names left out
+        let total = lines.len().saturating_sub(1);
+        if let Some(patterns) = self.piped_names() {
+            let name_column = self.prefix_columns().len();
+            let mut rows = lines.split_off(1.min(lines.len()));
+            rows.retain(|row| {
+                split_columns(&header, row)
+                    .get(name_column)
+                    .map_or(false, |name| patterns.iter().any(|p| p.is_match(name)))
+            });
+            lines.append(&mut rows);
+        }
+
+        // jsonpaths were already sorted by kubectl, columns are sorted here
+        if let Some(sort_by) = self.sort_by().filter(|s| !is_jsonpath(s)) {
+            if !lines.is_empty() {
+                sort::sort_rows(&header, &mut lines[1..], sort_by);
+            }
+        } else if self.ranks_frecent() && lines.len() > 1 {
+            let scores = frecency::scores(
+                self.context.as_deref(),
+                self.namespace.as_deref(),
+                &self.resource,
+            );
+            let score = |row: &String| {
+                let name = split_columns(&header, row).into_iter().next();
+                name.and_then(|n| scores.get(&n).copied()).unwrap_or(0.0)
+            };
+            // stable so the items never chosen keep kubectl's order
+            lines[1..].sort_by(|a, b| score(b).partial_cmp(&score(a)).unwrap());
+        }
+
+        // listings without a RESTARTS column aren't filtered
+        let layout = Arc::new(Layout::new(
+            &header,
+            &self.resource,
+            self.config.restarts,
+            self.view(),
+        ));
+        let restarting_only = self.restarting && layout.has_restarts();
+        let out = KubectlOutput {
+            header: layout.render(&header, false).unwrap_or(header),
+            total,
+            items: lines
+                .iter()
+                .skip(1)
+                .filter(|i| !restarting_only || layout.restarting(i))
+                .cloned()
+                .map(|i| {
+                    KubectlItem::new(
+                        i,
+                        self.resource.clone(),
+                        self.bindings.clone(),
+                        layout.clone(),
+                    )
+                })
+                .collect(),
+        };
+        info!(resource = %self.resource, items = out.items.len(), "listed items");
+
+        Some(out)
+    }
+
+    // kubectl get in every context and namespace at once, each row prefixed by where it came from
+    // a listing that fails is reported and left out rather than failing the whole thing
+    fn kubectl_get_each(&self) -> Option<Vec<String>> {
+        let contexts: Vec<Option<&str>> = if self.multi_context() {
+            self.contexts.iter().map(|c| Some(c.as_str())).collect()
+        } else {
+            vec![None]
+        };
+        let namespaces: Vec<Option<&str>> = if self.multi_namespace() {
+            self.namespaces.iter().map(|n| Some(n.as_str())).collect()
+        } else {
+            vec![self.namespace.as_deref()]
+        };
+
+        // with -A kubectl puts the NAMESPACE column in itself
+        let mut prefix_columns = Vec::new();
+        if self.multi_context() {
+            prefix_columns.push("CONTEXT");
+        }
+        if self.multi_namespace() {
+            prefix_columns.push("NAMESPACE");
+        }
+        let prefix_values = |context: Option<&str>, namespace: Option<&str>| -> Vec<String> {
+            let mut values = Vec::new();
+            if self.multi_context() {
+                values.push(context.unwrap_or_default().to_string());
+            }
+            if self.multi_namespace() {
+                values.push(namespace.unwrap_or_default().to_string());
+            }
+            values
+        };
+
+        let listings: Vec<(Vec<String>, Option<CaptureData>)> = thread::scope(|scope| {
+            let handles: Vec<_> = contexts
+                .iter()
+                .flat_map(|c| namespaces.iter().map(move |n| (*c, *n)))
+                .map(|(context, namespace)| {
+                    let prefix = prefix_values(context, namespace);
+                    scope.spawn(move || {
+                        let mut cmd = self.kubectl_get_cmd_in(namespace);
+                        if let Some(context) = context {
+                            cmd = cmd.arg("--context").arg(context);
+                        }
+                        (prefix, execute_cached(cmd))
+                    })
+                })
+                .collect();
+            handles.into_iter().filter_map(|h| h.join().ok()).collect()
+        });
+        // every listing is split by its own header then laid out again as one table
+        // so the columns line up across contexts and namespaces
+        let mut header = None;
+        let mut rows = Vec::new();
+        for (prefix, captured) in &listings {
+            let captured = match captured {
+                Some(captured) if captured.exit_status.success() => captured,
+                Some(captured) => {
+                    eprint!("{}: {}", prefix.join(" "), captured.stderr_str());
+                    continue;
+                }
+                None => continue,
+            };
+
+            let mut lines = match self.listing(&captured.stdout_str()) {
+                Some(lines) => lines.into_iter(),
+                None => continue,
+            };
+            let listing_header = match lines.next() {
+                Some(listing_header) => listing_header,
+                None => continue,
+            };
+            header.get_or_insert_with(|| {
+                let mut columns: Vec<String> =
+                    prefix_columns.iter().map(|c| c.to_string()).collect();
+                columns.extend(header_columns(&listing_header));
+                columns
+            });
+            rows.extend(lines.map(|l| {
+                let mut row = prefix.clone();
+                row.extend(split_columns(&listing_header, &l));
+                row
+            }));
+        }
+
+        let mut table = vec![header?];
+        table.extend(rows);
+        Some(render_columns(&table))
+    }
+
+    // kubectl get --watch sending each row to skim as it arrives
+    // returns the header and the still running watch process to stop once skim exits
+    fn kubectl_watch(&mut self, tx_item: SkimItemSender) -> Option<(String, Popen)> {
+        throttle();
+        let mut watch = supervisor::spawn(
+            self.kubectl_get_cmd()
+                .arg("--watch")
+                .stdout(Redirection::Pipe)
+                .stderr(NullFile),
+        )?;
+
+        let mut lines = BufReader::new(watch.stdout.take()?).lines();
+        let header = match lines.next() {
+            Some(Ok(header)) => header,
+            _ => {
+                supervisor::release(watch);
+                return None;
+            }
+        };
+        self.header = header.clone();
+        self.add_column_bindings(&header);
+
+        let resource = self.resource.clone();
+        let bindings = self.bindings.clone();
+        let layout = Arc::new(Layout::new(
+            &header,
+            &self.resource,
+            self.config.restarts,
+            self.view(),
+        ));
+        let header = layout.render(&header, false).unwrap_or(header);
+        if !self.no_status {
+            let _ = tx_item.send(Arc::new(header.clone()));
+        }
+        thread::spawn(move || {
+            for line in lines.map_while(Result::ok) {
+                let item =
+                    KubectlItem::new(line, resource.clone(), bindings.clone(), layout.clone());
+                if tx_item.send(Arc::new(item)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Some((header, watch))
+    }
+
+    // fill our function key bindings based on the number of columns
+    // 19 is the number of function keys on my full sized keyboard as a sane default
+    // the first 9 are on alt-1 to alt-9 too, for keyboards without function keys,
+    // and alt-0 picks from every column
+    // the columns are numbered as bindings see them, without the context and namespace columns
+    // that come off each row first
+    fn add_column_bindings(&mut self, header: &str) {
+        let header_columns = header_columns(header);
+        let item_columns: Vec<String> = header_columns
+            .iter()
+            .skip(self.prefix_columns().len())
+            .cloned()
+            .collect();
+        self.add_columns(&item_columns);
+        let restarts = header_columns.iter().any(|c| c == "RESTARTS");
+        self.add_listing_bindings(header_columns, item_columns, restarts);
+    }
+
+    // a binding for each column after the name, up to f19 and alt-9
+    fn add_columns(&mut self, item_columns: &[String]) {
+        for (i, name) in item_columns.iter().skip(1).take(19).enumerate() {
+            self.add_listing_binding(Column::new(name.clone(), i + 1));
+            if i < 9 {
+                self.add_listing_binding(Column::alt(name.clone(), i + 1));
+            }
+        }
+    }
+
+    // the bindings that depend on the listing without being tied to one column
+    // sorting is of the whole listing so it can go by the context and namespace columns too
+    fn add_listing_bindings(
+        &mut self,
+        header_columns: Vec<String>,
+        item_columns: Vec<String>,
+        restarts: bool,
+    ) {
+        if !item_columns.is_empty() {
+            self.add_listing_binding(PickColumn::new(item_columns.clone()));
+            self.add_listing_binding(CopyColumn::new(item_columns));
+        }
+        if !header_columns.is_empty() {
+            self.add_listing_binding(Sort::new(header_columns, self.sort_by().map(String::from)));
+        }
+        if restarts {
+            self.add_listing_binding(Restarting);
+        }
+        if self.table.is_none() {
+            self.add_listing_binding(Wide);
+            self.add_listing_binding(Labels);
+        }
+        self.add_listing_binding(Scroll::left());
+        self.add_listing_binding(Scroll::right());
+
+        // last so the page has every other binding on it
+        let help_rows = self.help_rows();
+        self.add_listing_binding(Help::new(help_rows, self.pager.clone()));
+    }
+
+    // the bindings for the current resource, the column bindings and the picker's own keys
+    // as tab separated rows under a heading each
+    fn help_rows(&self) -> Vec<String> {
+        let help_key = self
+            .config
+            .keys
+            .get("help")
+            .map_or("ctrl-h", String::as_str);
+        let mut bound = Vec::new();
+        if !help_key.is_empty() {
+            bound.push(("Help".to_string(), help_key.to_string()));
+        }
+        let mut columns = Vec::new();
+        for b in self.bindings.lock().unwrap().values() {
+            if !b.runs_for(&self.resource) {
+                continue;
+            }
+            let key = if b.key().is_empty() {
+                "enter".to_string()
+            } else {
+                b.key()
+            };
+            if is_column_binding(&b.name()) {
+                columns.push((b.description(), key));
+            } else {
+                bound.push((b.description(), key));
+            }
+        }
+        bound.sort();
+        // columns are described as index:name and one on both f1 and alt-1 is listed once
+        columns.sort_by_key(|(description, key)| {
+            let index = description.split(':').next().unwrap_or_default();
+            (
+                index.parse::<usize>().unwrap_or_default(),
+                key.starts_with("alt-"),
+            )
+        });
+        let mut merged: Vec<(String, String)> = Vec::new();
+        for (description, key) in columns {
+            match merged.last_mut() {
+                Some((last, keys)) if *last == description => {
+                    keys.push_str(" / ");
+                    keys.push_str(&key);
+                }
+                _ => merged.push((description, key)),
+            }
+        }
+
+        let picker_keys = PICKER_KEYS
+            .iter()
+            .map(|(description, key, _)| (description.to_string(), key.to_string()))
+            .chain(
+                SKIM_KEYS
+                    .iter()
+                    .map(|(description, keys)| (description.to_string(), keys.to_string())),
+            )
+            .collect();
+
+        let mut rows = Vec::new();
+        let sections = vec![
+            (format!("Bindings for {}", self.resource), bound),
+            ("Columns".to_string(), merged),
+            ("Picker".to_string(), picker_keys),
+        ];
+        for (heading, keys) in sections {
+            if keys.is_empty() {
+                continue;
+            }
+            if !rows.is_empty() {
+                rows.push(String::new());
+            }
+            rows.push(heading);
+            rows.extend(keys.iter().map(|(description, key)| {
+                format!("  {}", theme::binding_preview(description, key))
+            }));
+        }
+        rows
+    }
+}
+
+// bindings from the config directory, scripts first then lua plugins
+fn load_extensions() -> Result<Vec<Arc<dyn Binding + Send + Sync>>, String> {
+    let mut extensions: Vec<Arc<dyn Binding + Send + Sync>> = Vec::new();
+    for script in scripts::load()? {
+        extensions.push(Arc::new(script));
+    }
+    for plugin in plugins::load()? {
+        extensions.push(Arc::new(plugin));
+    }
+    Ok(extensions)
+}
+
+// a duration flag in seconds, either a plain number or with units like kubectl takes
+// example: 90 or 1m30s
+fn parse_duration(duration: &str) -> Result<Duration, String> {
+    duration
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+        .or_else(|| age::parse(duration))
+        .filter(|d| !d.is_zero())
+        .ok_or_else(|| {
+            format!(
+                "Invalid duration {}, expected something like 30s or 2m",
+                duration
+            )
+        })
+}
+
+// whether --sort-by is a JSONPath for kubectl rather than a column name
+fn is_jsonpath(sort_by: &str) -> bool {
+    sort_by.starts_with('.') || sort_by.starts_with('{')
+}
+
+// the bindings for each column, which the help page lists together whatever keys they're on
+fn is_column_binding(name: &str) -> bool {
+    name.strip_prefix("alt-")
+        .unwrap_or(name)
+        .strip_prefix("column-")
+        .map_or(false, |n| n.parse::<usize>().is_ok())
+}
+
+// the selected items in the order tab toggled them on, going by the log of every toggle
+// an item toggled twice was unselected in between, and items selected all at once like with ctrl-a
+// follow in the order they were listed
+fn in_selection_order(mut items: Vec<Arc<dyn SkimItem>>, toggles: &str) -> Vec<Arc<dyn SkimItem>> {
+    let mut order: Vec<&str> = Vec::new();
+    for toggled in toggles.lines() {
+        match order.iter().position(|o| *o == toggled) {
+            Some(i) => {
+                order.remove(i);
+            }
+            None => order.push(toggled),
+        }
+    }
+    // stable so everything not in the log keeps its place
+    items.sort_by_key(|i| {
+        order
+            .iter()
+            .position(|o| *o == i.output())
+            .unwrap_or(order.len())
+    });
+    items
+}
+
+// what was really selected in the picker, given the items kept selected from the last visit and
+// the log of toggles
+// skim gives the item under the cursor when nothing is selected, so anything it gives that wasn't
+// toggled an odd number of times was never selected
+// after selecting, deselecting or inverting everything skim's selection is the one that counts
+fn chosen_items(
+    kept: Vec<Arc<dyn SkimItem>>,
+    selected: &[Arc<dyn SkimItem>],
+    toggles: &str,
+) -> Vec<Arc<dyn SkimItem>> {
+    if toggles.lines().any(str::is_empty) {
+        return selected.to_vec();
+    }
+    let mut chosen = kept;
+    for item in selected {
+        let toggled = toggles.lines().filter(|t| *t == item.output()).count();
+        if toggled % 2 == 1 && !chosen.iter().any(|c| c.output() == item.output()) {
+            chosen.push(item.clone());
+        }
+    }
+    chosen
+}
+
+// names or glob patterns like api-* read from stdin when it isn't a terminal, one per line
+// lines from kubectl get -o name have their resource type taken off
+// none when nothing was piped in
+// example: pod/api-0 -> ^api-0$
+fn read_piped_names() -> Option<Vec<Regex>> {
+    if unsafe { libc::isatty(libc::STDIN_FILENO) } == 1 {
+        return None;
+    }
+
+    let patterns: Vec<Regex> = BufReader::new(io::stdin())
+        .lines()
+        .filter_map(Result::ok)
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let name = line.rsplit('/').next().unwrap_or(&line);
+            let pattern = regex::escape(name).replace(r"\*", ".*").replace(r"\?", ".");
+            Regex::new(&format!("^{}$", pattern)).ok()
+        })
+        .collect();
+    if patterns.is_empty() {
+        None
+    } else {
+        Some(patterns)
+    }
+}
+
+fn main() {
+    let mut opts: Opts = Opts::parse();
+    if let Some(shell) = &opts.shell_init {
+        print!("{}", shell_init::script(shell));
+        return;
+    }
+    if opts.tmux && tmux::available() {
+        std::process::exit(tmux::rerun().unwrap_or(EXIT_FAILED));
+    }
+    match &opts.command {
+        Some(Command::Completions { shell }) => {
+            completions::print(&mut Opts::into_app(), shell);
+            return;
+        }
+        Some(Command::Cache {
+            command: CacheCommand::Clear,
+        }) => {
+            if let Err(e) = cache::clear() {
+                eprintln!("{}", e);
+                std::process::exit(EXIT_FAILED);
+            }
+            return;
+        }
+        Some(Command::Complete { kind }) => {
+            for candidate in completions::candidates(kind) {
+                println!("{}", candidate);
+            }
+            return;
+        }
+        // needs the config for the audit log
+        Some(Command::Undo) | None => {}
+    }
+    if let Err(e) = logging::init(opts.verbose, opts.log_file.as_deref()) {
+        eprintln!("{}", e);
+        std::process::exit(EXIT_FAILED);
+    }
+    opts.config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(EXIT_FAILED);
+        }
+    };
+    if let Err(e) = opts.use_profile() {
+        eprintln!("{}", e);
+        std::process::exit(EXIT_FAILED);
+    }
+    opts.resource = opts.resource_arg.clone().unwrap_or_default();
+    // --history, --bookmarks and --resume start from a listing from before, in whichever context it was in
+    if opts.history {
+        match recent::pick(&recent::load()) {
+            Some(selection) => opts.start_at(selection.context.clone(), selection.navigation()),
+            None => std::process::exit(EXIT_ABORTED),
+        }
+    }
+    if opts.bookmarks {
+        match bookmarks::pick(&bookmarks::load()) {
+            Some(bookmark) => opts.start_at(bookmark.context.clone(), bookmark.navigation()),
+            None => std::process::exit(EXIT_ABORTED),
+        }
+    }
+    if opts.resume {
+        match session::load() {
+            Ok(session) => opts.start_at(session.context.clone(), session.navigation()),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(EXIT_FAILED);
+            }
+        }
+    }
+    opts.extensions = match load_extensions() {
+        Ok(extensions) => extensions,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(EXIT_FAILED);
+        }
+    };
+    if let Err(e) = opts.check_keys() {
+        eprintln!("{}", e);
+        std::process::exit(EXIT_FAILED);
+    }
+    theme::set_no_color(opts.no_color);
+    match theme::Theme::resolve(opts.theme.as_deref(), &opts.config.theme) {
+        Ok(theme) => theme::set(theme),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(EXIT_FAILED);
+        }
+    }
+    set_global_args(opts.global_args(None));
+    if opts.all_contexts {
+        opts.contexts = capture(
+            kubectl_base_cmd(None, "config", "get-contexts".to_string())
+                .arg("--output")
+                .arg("name"),
+        )
+        .unwrap_or_default()
+        .lines()
+        .map(String::from)
+        .collect();
+    }
+    set_dry_run(opts.dry_run);
+    set_counts_selected(!opts.no_status);
+    set_request_timeout(opts.request_timeout);
+    set_rate_limit(opts.rate_limit.or(opts.config.rate_limit));
+    cache::set_ttl(opts.cache_ttl);
+    picker::set_assume_yes(opts.yes);
+    progress::set_hidden(opts.quiet);
+    audit::set_log(opts.config.audit_log.clone());
+    if let Some(Command::Undo) = opts.command {
+        std::process::exit(opts.undo());
+    }
+
+    // ? asks which namespace and resource to list from what the cluster has
+    if opts.namespace.as_deref() == Some("?") {
+        match resources::pick_namespace() {
+            Some(namespace) => opts.namespace = Some(namespace),
+            None => std::process::exit(EXIT_ABORTED),
+        }
+        // impersonating a service account depends on the namespace
+        set_global_args(opts.global_args(None));
+    }
+    // without a resource there's only a choice to make when someone is there to make it,
+    // so scripts and tables from stdin list pods as they always have
+    if opts.resource.is_empty() && (opts.batch() || opts.stdin) {
+        opts.resource = "pod".to_string();
+    }
+    if opts.resource.is_empty() || opts.resource == "?" {
+        match resources::pick() {
+            Some(resource) => opts.resource = resource,
+            None => std::process::exit(EXIT_ABORTED),
+        }
+    }
+    supervisor::install();
+    // a proxy only serves the one context, and rows from stdin don't need one
+    if (opts.proxy || opts.config.proxy || opts.proxy_address.is_some())
+        && opts.contexts.is_empty()
+        && !opts.stdin
+    {
+        match proxy::Proxy::start(opts.proxy_address.as_deref()) {
+            Ok(proxy) => set_proxy_args(Some(proxy.args())),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(EXIT_FAILED);
+            }
+        }
+    }
+    // names piped in narrow down the listing of the resource given, the same as --columns
+    // unless stdin is the whole table
+    if opts.stdin {
+        let table: Vec<String> = BufReader::new(io::stdin())
+            .lines()
+            .filter_map(Result::ok)
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+        if table.is_empty() {
+            eprintln!("Nothing to pick from on stdin");
+            std::process::exit(EXIT_NO_MATCH);
+        }
+        opts.table = Some(table);
+    } else if let Some(patterns) = read_piped_names() {
+        opts.piped = Some((opts.resource.clone(), patterns));
+    }
+    // --columns is for the resource given, so it shouldn't follow navigation to another one
+    if let Some(columns) = opts.columns.take() {
+        opts.config.columns.insert(opts.resource.clone(), columns);
+    }
+
+    // navigation bindings relaunch the picker on a new listing until something else ends the run
+    // in loop mode only aborting the picker ends the run
+    let code = loop {
+        // check_keys caught any clash at startup so this is only a safeguard
+        if let Err(e) = opts.setup_bindings() {
+            eprintln!("{}", e);
+            break EXIT_FAILED;
+        }
+
+        let outcome = opts.run();
+        if !opts.batch() && opts.table.is_none() {
+            session::save(&opts.session());
+        }
+
+        // output from a binding that failed part way is still shown, before what went wrong
+        let (outcome, errors, paged) = match outcome {
+            Some(Outcome::Errored(output, errors)) => {
+                (Some(Outcome::Output(output)), Some(errors), true)
+            }
+            Some(Outcome::Structured(output)) => (Some(Outcome::Output(output)), None, false),
+            other => (other, None, true),
+        };
+        match outcome {
+            Some(Outcome::Navigate(navigation)) => opts.navigate(navigation),
+            // the user can pipe to a reader of choice if desired
+            // otherwise long output goes through a pager when printing to a terminal, unless it's
+            // names or json meant for another program
+            Some(Outcome::Output(final_output)) => {
+                let final_output = opts.pipe_output(final_output);
+                if opts.no_pager || !paged {
+                    print!("{}", final_output);
+                } else {
+                    pager::show(&final_output, opts.pager.as_deref());
+                }
+                if let Some(errors) = &errors {
+                    eprint!("{}", errors);
+                }
+                if !opts.loop_mode || opts.batch() {
+                    break if errors.is_some() { EXIT_FAILED } else { 0 };
+                }
+
+                // keep the output on screen above the picker when it reopens
+                if !final_output.is_empty() && !final_output.ends_with('\n') {
+                    println!();
+                }
+                let _ = io::stdout().flush();
+            }
+            // escape is the way out of loop mode so isn't an error there
+            Some(Outcome::Aborted) if opts.loop_mode => break 0,
+            Some(Outcome::Aborted) => break EXIT_ABORTED,
+            Some(Outcome::NoMatch) if opts.exit_0 => break 0,
+            Some(Outcome::NoMatch) => break EXIT_NO_MATCH,
+            Some(Outcome::Failed(reason)) => {
+                if !opts.quiet {
+                    eprintln!("{}", reason);
+                }
+                break EXIT_FAILED;
+            }
+            // kubectl has already said what went wrong on stderr
+            None => break EXIT_FAILED,
+        }
+    };
+
+    supervisor::shutdown();
+    std::process::exit(code);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_duration_takes_seconds_or_units() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("1m30s"), Ok(Duration::from_secs(90)));
+    }
+
+    #[test]
+    fn parse_duration_refuses_zero_and_nonsense() {
+        assert!(parse_duration("0").is_err());
+        assert!(parse_duration("0s").is_err());
+        assert!(parse_duration("soon").is_err());
+        assert!(parse_duration("").is_err());
+    }
+
+    #[test]
+    fn in_selection_order_follows_the_toggles() {
+        let items: Vec<Arc<dyn SkimItem>> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|name| Arc::new(name.to_string()) as Arc<dyn SkimItem>)
+            .collect();
+        // b was toggled off again, so it keeps its listed place with d
+        let ordered: Vec<String> = in_selection_order(items, "c\na\nb\nb\n")
+            .iter()
+            .map(|i| i.output().to_string())
+            .collect();
+        assert_eq!(ordered, vec!["c", "a", "b", "d"]);
+    }
+
+    #[test]
+    fn chosen_items_keep_the_last_visit_and_drop_the_cursor() {
+        let item = |name: &str| Arc::new(name.to_string()) as Arc<dyn SkimItem>;
+        let names = |items: Vec<Arc<dyn SkimItem>>| -> Vec<String> {
+            items.iter().map(|i| i.output().to_string()).collect()
+        };
+        // c is only under the cursor and b was toggled off again
+        assert_eq!(
+            names(chosen_items(vec![], &[item("c")], "")),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            names(chosen_items(
+                vec![item("a")],
+                &[item("b"), item("d")],
+                "b\nd\nb\n"
+            )),
+            vec!["a", "d"]
+        );
+        // deselecting everything leaves it to skim
+        assert_eq!(
+            names(chosen_items(vec![item("a")], &[item("c")], "\n")),
+            vec!["c"]
+        );
+    }
 
-    // the user can pipe to a reader of choice if desired
-    // so just print to stdout
-    // perhaps in future add optional inbuilt readers such as `bat`
-    if let Some(final_output) = opts.run() {
-        print!("{}", final_output);
+    #[test]
+    fn is_column_binding_matches_only_column_names() {
+        assert!(is_column_binding("column-3"));
+        assert!(is_column_binding("alt-column-12"));
+        assert!(!is_column_binding("column-"));
+        assert!(!is_column_binding("copy-column"));
     }
 }
@@ -0,0 +1,81 @@
+use crate::supervisor;
+use crate::theme;
+use std::env;
+use std::fs::OpenOptions;
+use std::mem;
+use subprocess::Exec;
+
+// shows output through a pager when it won't fit on the terminal
+// anything piped or short enough is printed straight to stdout
+pub fn show(output: &str, pager: Option<&str>) {
+    let rows = match terminal_rows() {
+        Some(rows) => rows,
+        None => {
+            print!("{}", output);
+            return;
+        }
+    };
+
+    if output.lines().count() < rows {
+        print!("{}", output);
+        return;
+    }
+
+    let paged = supervisor::join(Exec::shell(command(pager)).stdin(output));
+    if paged.map_or(true, |status| !status.success()) {
+        print!("{}", output);
+    }
+}
+
+// pages output on the terminal however short it is, even when stdout is piped somewhere
+// for things like the help page that aren't output of the tool
+pub fn page_on_tty(output: &str, pager: Option<&str>) {
+    let tty = match OpenOptions::new().write(true).open("/dev/tty") {
+        Ok(tty) => tty,
+        Err(_) => return,
+    };
+    supervisor::join(Exec::shell(command(pager)).stdin(output).stdout(tty));
+}
+
+// picks the pager command: the one passed in, then $PAGER, then bat if installed, falling back to less
+fn command(pager: Option<&str>) -> String {
+    if let Some(pager) = pager {
+        return pager.into();
+    }
+    if let Ok(pager) = env::var("PAGER") {
+        if !pager.is_empty() {
+            return pager;
+        }
+    }
+
+    // debian and ubuntu ship bat as batcat
+    let color = if theme::colored() { "auto" } else { "never" };
+    for bat in &["bat", "batcat"] {
+        if on_path(bat) {
+            return format!("{} --paging=always --plain --color={}", bat, color);
+        }
+    }
+
+    "less -R".into()
+}
+
+fn on_path(name: &str) -> bool {
+    env::var_os("PATH")
+        .map(|paths| env::split_paths(&paths).any(|dir| dir.join(name).is_file()))
+        .unwrap_or(false)
+}
+
+// the height of the terminal stdout is attached to, none when stdout isn't a terminal
+fn terminal_rows() -> Option<usize> {
+    unsafe {
+        if libc::isatty(libc::STDOUT_FILENO) != 1 {
+            return None;
+        }
+
+        let mut size: libc::winsize = mem::zeroed();
+        if libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) != 0 || size.ws_row == 0 {
+            return None;
+        }
+        Some(size.ws_row as usize)
+    }
+}
@@ -0,0 +1,95 @@
+use crate::theme;
+use skim::prelude::*;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::io::AsRawFd;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// with --yes confirmations are taken as answered yes
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+
+// a small picker for choosing one of a few things, such as a column, inside a binding
+// returns None when nothing was chosen
+pub fn pick_one(prompt: &str, choices: &[String]) -> Option<String> {
+    let prompt = format!("{}> ", prompt);
+    let colors = theme::skim_colors();
+    let options = SkimOptionsBuilder::default()
+        .height(Some("30%"))
+        .color(Some(&colors))
+        .reverse(true)
+        .prompt(Some(&prompt))
+        .build()
+        .unwrap();
+
+    let (tx_item, rx_item): (SkimItemSender, SkimItemReceiver) = unbounded();
+    for choice in choices {
+        tx_item.send(Arc::new(choice.clone())).ok()?;
+    }
+    drop(tx_item);
+
+    let selected = Skim::run_with(&options, Some(rx_item))?
+        .selected_items
+        .first()?
+        .output()
+        .to_string();
+    Some(selected)
+}
+
+// switches the terminal to and from the alternate screen around a full screen picker
+// so whatever was on screen before is left as it was once the picker closes
+pub fn alternate_screen(enter: bool) {
+    let sequence = if enter { "\x1b[?1049h" } else { "\x1b[?1049l" };
+    if let Ok(mut tty) = OpenOptions::new().write(true).open("/dev/tty") {
+        let _ = tty.write_all(sequence.as_bytes());
+    }
+}
+
+// the width of the terminal the picker draws on, which is still there when stdout is piped
+pub fn terminal_width() -> Option<usize> {
+    let tty = OpenOptions::new().write(true).open("/dev/tty").ok()?;
+    unsafe {
+        let mut size: libc::winsize = std::mem::zeroed();
+        if libc::ioctl(tty.as_raw_fd(), libc::TIOCGWINSZ, &mut size) != 0 || size.ws_col == 0 {
+            return None;
+        }
+        Some(size.ws_col as usize)
+    }
+}
+
+pub fn set_assume_yes(yes: bool) {
+    ASSUME_YES.store(yes, Ordering::SeqCst);
+}
+
+// asks on the terminal before a binding does something dangerous to the listed items
+// anything but y or yes is a no, as is having no terminal to ask on
+// typed asks for yes in full so it can't be answered by habit
+pub fn confirm(warning: &str, items: &[String], typed: bool) -> bool {
+    if ASSUME_YES.load(Ordering::SeqCst) {
+        return true;
+    }
+
+    let tty = match OpenOptions::new().read(true).write(true).open("/dev/tty") {
+        Ok(tty) => tty,
+        Err(_) => return false,
+    };
+
+    let mut prompt = format!("warning: {}\n", warning);
+    for item in items {
+        prompt.push_str(&format!("  {}\n", item));
+    }
+    prompt.push_str(if typed {
+        "Type yes to continue: "
+    } else {
+        "Continue? [y/N] "
+    });
+    if (&tty).write_all(prompt.as_bytes()).is_err() {
+        return false;
+    }
+
+    let mut answer = String::new();
+    if BufReader::new(&tty).read_line(&mut answer).is_err() {
+        return false;
+    }
+    let answer = answer.trim().to_lowercase();
+    answer == "yes" || (!typed && answer == "y")
+}
@@ -0,0 +1,208 @@
+use crate::bindings::{Binding, BindingContext, Navigation};
+use crate::config::config_dir;
+use crate::kubectl::{dry_run, execute, kubectl_base_cmd, kubectl_get_json};
+use mlua::{Function, Lua, LuaSerdeExt, Table, Value, Variadic};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::warn;
+
+// bindings written in lua, loaded from the plugins directory of the config
+// a plugin is a script that returns a table describing the binding
+//   return {
+//     key = "alt-g",
+//     description = "Images",
+//     accepts = {"pods", "pod", "po"},
+//     mutates = false,
+//     run = function(ctx) return "output" end,
+//     navigate = function(ctx) return {resource = "nodes", names = {"node-a"}} end,
+//   }
+// ctx has namespace, resource, names and columns
+// a plugin can call kubectl with anything so it counts as changing things unless it sets mutates = false
+// plugins call back into kubectl with
+//   kubectl(args...) -> stdout, ok
+//   kubectl_json(namespace, resource, names) -> list of objects
+// see examples/plugins for some to start from
+
+// where the table the plugin returned is kept in its lua state
+const BINDING: &str = "binding";
+
+// each plugin has its own lua state, locked since it can only run on one thread at a time
+pub struct Plugin {
+    lua: Mutex<Lua>,
+    key: String,
+    description: String,
+    accepts: Vec<String>,
+    mutates: bool,
+}
+
+// loads every .lua file in the plugins directory
+// a plugin that fails to load is an error rather than a binding that silently goes missing
+pub fn load() -> Result<Vec<Plugin>, String> {
+    let dir = match config_dir() {
+        Some(dir) => dir.join("plugins"),
+        None => return Ok(Vec::new()),
+    };
+
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", dir.display(), e)),
+    };
+
+    let mut paths: Vec<_> = entries
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.extension().map_or(false, |e| e == "lua"))
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|p| {
+            Plugin::load(p).map_err(|e| format!("Failed to load plugin {}: {}", p.display(), e))
+        })
+        .collect()
+}
+
+impl Plugin {
+    fn load(path: &Path) -> mlua::Result<Plugin> {
+        let source = fs::read_to_string(path).map_err(mlua::Error::external)?;
+        let lua = Lua::new();
+        register_api(&lua)?;
+
+        // the table borrows the lua state so read what's needed before moving it
+        let (key, description, accepts, mutates) = {
+            let binding: Table = lua
+                .load(&source)
+                .set_name(&path.to_string_lossy())?
+                .eval()?;
+
+            // fail now rather than when the key is pressed
+            let _: Function = binding.get("run")?;
+
+            let described = (
+                binding.get("key")?,
+                binding.get("description")?,
+                binding
+                    .get::<_, Option<Vec<String>>>("accepts")?
+                    .unwrap_or_default(),
+                binding.get::<_, Option<bool>>("mutates")?.unwrap_or(true),
+            );
+            lua.set_named_registry_value(BINDING, binding)?;
+            described
+        };
+
+        Ok(Plugin {
+            lua: Mutex::new(lua),
+            key,
+            description,
+            accepts,
+            mutates,
+        })
+    }
+}
+
+impl Binding for Plugin {
+    fn run(&self, ctx: &BindingContext) -> Option<String> {
+        let lua = self.lua.lock().unwrap();
+        let output = (|| -> mlua::Result<Option<String>> {
+            let binding: Table = lua.named_registry_value(BINDING)?;
+            let run: Function = binding.get("run")?;
+            run.call(context(&lua, ctx)?)
+        })();
+
+        match output {
+            Ok(output) => output,
+            Err(e) => Some(format!("{} failed: {}\n", self.description, e)),
+        }
+    }
+    fn key(&self) -> String {
+        self.key.clone()
+    }
+    fn description(&self) -> String {
+        self.description.clone()
+    }
+    fn accepts(&self) -> Vec<String> {
+        self.accepts.clone()
+    }
+    fn mutates(&self) -> bool {
+        self.mutates
+    }
+    fn navigate(&self, ctx: &BindingContext) -> Option<Navigation> {
+        let lua = self.lua.lock().unwrap();
+        let navigation = (|| -> mlua::Result<Option<Navigation>> {
+            let binding: Table = lua.named_registry_value(BINDING)?;
+            let navigate = match binding.get::<_, Option<Function>>("navigate")? {
+                Some(navigate) => navigate,
+                None => return Ok(None),
+            };
+            let target = match navigate.call::<_, Option<Table>>(context(&lua, ctx)?)? {
+                Some(target) => target,
+                None => return Ok(None),
+            };
+
+            Ok(Some(Navigation {
+                resource: target.get("resource")?,
+                namespace: target
+                    .get::<_, Option<String>>("namespace")?
+                    .or_else(|| ctx.namespace.clone()),
+                names: target
+                    .get::<_, Option<Vec<String>>>("names")?
+                    .unwrap_or_default(),
+                selector: target.get("selector")?,
+                field_selector: target.get("field_selector")?,
+                ..Default::default()
+            }))
+        })();
+
+        // falling back to run lets the plugin explain why it couldn't navigate
+        navigation.unwrap_or_else(|e| {
+            warn!(plugin = %self.description, error = %e, "navigate failed");
+            None
+        })
+    }
+}
+
+// the binding context as a lua table
+fn context<'lua>(lua: &'lua Lua, ctx: &BindingContext) -> mlua::Result<Table<'lua>> {
+    let table = lua.create_table()?;
+    table.set("namespace", ctx.namespace.clone())?;
+    table.set("resource", ctx.resource.clone())?;
+    table.set("names", ctx.names.clone())?;
+    table.set("columns", ctx.columns.clone())?;
+    Ok(table)
+}
+
+// the kubectl functions plugins can call
+// both go through the same choke point as the built in bindings so dry run and tracing apply
+// to plugins too, and the audit log records their commands when the plugin counts as mutating
+fn register_api(lua: &Lua) -> mlua::Result<()> {
+    let kubectl = lua.create_function(|_, args: Variadic<String>| {
+        let mut args = args.into_iter();
+        let command = args.next().unwrap_or_default();
+        let cmd = kubectl_base_cmd(None, &command, None).args(&args.collect::<Vec<_>>());
+        if let Some(cmdline) = dry_run(&cmd) {
+            return Ok((cmdline, true));
+        }
+
+        Ok(match execute(cmd) {
+            Some(output) => (output.stdout_str(), output.exit_status.success()),
+            None => (String::new(), false),
+        })
+    })?;
+    lua.globals().set("kubectl", kubectl)?;
+
+    let kubectl_json = lua.create_function(
+        |lua, (namespace, resource, names): (Option<String>, String, Option<Vec<String>>)| {
+            match kubectl_get_json(namespace.as_deref(), &resource, &names.unwrap_or_default()) {
+                Some(items) => lua.to_value(&items),
+                None => Ok(Value::Nil),
+            }
+        },
+    )?;
+    lua.globals().set("kubectl_json", kubectl_json)?;
+
+    Ok(())
+}
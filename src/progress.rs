@@ -0,0 +1,105 @@
+use crate::picker;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// how far a binding that runs a command for each item has got, drawn on stderr as items finish
+// so a long run over many items doesn't look stuck
+// nothing is drawn for a single item or when stderr isn't a terminal
+
+// with --quiet nothing is drawn at all
+static HIDDEN: AtomicBool = AtomicBool::new(false);
+
+pub fn set_hidden(hidden: bool) {
+    HIDDEN.store(hidden, Ordering::Relaxed);
+}
+
+// whether anything is drawn on stderr at all
+fn shown() -> bool {
+    !HIDDEN.load(Ordering::Relaxed) && unsafe { libc::isatty(libc::STDERR_FILENO) } == 1
+}
+
+// the latest line of a command that takes a while, such as a drain or rollout status, drawn over
+// the last one so it's clear what it's waiting on
+pub fn show_line(line: &str) {
+    if !shown() || line.is_empty() {
+        return;
+    }
+    let width = picker::terminal_width().unwrap_or(80).saturating_sub(1);
+    let line: String = line.chars().take(width).collect();
+    eprint!("\r\x1b[K{}", line);
+    let _ = io::stderr().flush();
+}
+
+// clears the line drawn by show_line once the command is done
+pub fn clear_line() {
+    if shown() {
+        eprint!("\r\x1b[K");
+    }
+}
+
+const BAR_WIDTH: usize = 20;
+
+pub struct Progress {
+    action: String,
+    total: usize,
+    succeeded: usize,
+    failed: Vec<String>,
+    shown: bool,
+}
+
+impl Progress {
+    pub fn new(action: &str, total: usize) -> Progress {
+        let shown = total > 1 && shown();
+        Progress {
+            action: action.to_string(),
+            total,
+            succeeded: 0,
+            failed: Vec::new(),
+            shown,
+        }
+    }
+
+    // counts an item as done and redraws the bar with the item just done
+    // example: Patching [########            ] 8/20 api-7
+    pub fn step(&mut self, name: &str, succeeded: bool) {
+        if succeeded {
+            self.succeeded += 1;
+        } else {
+            self.failed.push(name.to_string());
+        }
+        if !self.shown {
+            return;
+        }
+
+        let done = self.succeeded + self.failed.len();
+        let filled = BAR_WIDTH * done / self.total.max(1);
+        let name: String = name.chars().take(40).collect();
+        eprint!(
+            "\r\x1b[K{} [{}{}] {}/{} {}",
+            self.action,
+            "#".repeat(filled),
+            " ".repeat(BAR_WIDTH - filled),
+            done,
+            self.total,
+            name
+        );
+        let _ = io::stderr().flush();
+    }
+
+    // replaces the bar with how many succeeded and which failed
+    // example: Patching: 18 succeeded, 2 failed (api-3, api-9)
+    pub fn finish(self) {
+        if !self.shown {
+            return;
+        }
+        let mut summary = format!("{}: {} succeeded", self.action, self.succeeded);
+        if !self.failed.is_empty() {
+            summary.push_str(&format!(
+                ", {} failed ({})",
+                self.failed.len(),
+                self.failed.join(", ")
+            ));
+        }
+        eprintln!("\r\x1b[K{}", summary);
+    }
+}
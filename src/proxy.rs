@@ -0,0 +1,98 @@
+use crate::kubectl::{current_context, kubectl_base_cmd};
+use crate::supervisor;
+use serde_json::json;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use subprocess::{NullFile, Popen, Redirection};
+
+// kubectl proxy for every kubectl get to go through, so the tls handshake and any auth plugin of
+// the cluster are paid for once by the proxy rather than by each listing and lookup
+// gets reach it through a kubeconfig of its own with the proxy as a plain http server and no user
+// the supervisor stops the proxy and removes the kubeconfig on exit, however the run ends
+pub struct Proxy {
+    kubeconfig: PathBuf,
+}
+
+impl Proxy {
+    // starts kubectl proxy on a free port for the current context, or uses the one at address
+    // example address: 127.0.0.1:8001
+    pub fn start(address: Option<&str>) -> Result<Proxy, String> {
+        // the proxy's kubeconfig lists in the namespace the context would have
+        let namespace = current_context().map_or_else(|| "default".to_string(), |(_, n)| n);
+        let (child, address) = match address {
+            Some(address) => (None, address.to_string()),
+            None => {
+                let (child, address) = spawn()?;
+                (Some(child), address)
+            }
+        };
+        let server = if address.starts_with("http") {
+            address
+        } else {
+            format!("http://{}", address)
+        };
+
+        let kubeconfig = match supervisor::temp_dir() {
+            Some(dir) => dir.join("proxy-kubeconfig"),
+            None => {
+                if let Some(child) = child {
+                    supervisor::release(child);
+                }
+                return Err("Failed to create a temp directory for the proxy".into());
+            }
+        };
+        let config = json!({
+            "apiVersion": "v1",
+            "kind": "Config",
+            "clusters": [{"name": "proxy", "cluster": {"server": server}}],
+            "contexts": [{"name": "proxy", "context": {"cluster": "proxy", "namespace": namespace}}],
+            "current-context": "proxy",
+        });
+        if let Err(e) = fs::write(&kubeconfig, config.to_string()) {
+            if let Some(child) = child {
+                supervisor::release(child);
+            }
+            return Err(format!("Failed to write {}: {}", kubeconfig.display(), e));
+        }
+        // dropping the child would wait for it, and the supervisor still has its pid to stop
+        if let Some(mut child) = child {
+            child.detach();
+        }
+
+        Ok(Proxy { kubeconfig })
+    }
+
+    // the flags for kubectl get to go through the proxy in place of the connection flags
+    pub fn args(&self) -> Vec<String> {
+        vec![
+            "--kubeconfig".into(),
+            self.kubeconfig.to_string_lossy().into_owned(),
+        ]
+    }
+}
+
+// runs kubectl proxy with the connection flags of the run and waits for it to say where it's serving
+// Starting to serve on 127.0.0.1:37021
+fn spawn() -> Result<(Popen, String), String> {
+    let mut child = supervisor::spawn(
+        kubectl_base_cmd(None, "proxy", None)
+            .arg("--port")
+            .arg("0")
+            .stdout(Redirection::Pipe)
+            .stderr(NullFile),
+    )
+    .ok_or("Failed to start kubectl proxy")?;
+
+    let mut line = String::new();
+    if let Some(stdout) = child.stdout.take() {
+        let _ = BufReader::new(stdout).read_line(&mut line);
+    }
+    match line.trim().strip_prefix("Starting to serve on ") {
+        Some(address) => Ok((child, address.to_string())),
+        None => {
+            supervisor::release(child);
+            Err("kubectl proxy didn't start".into())
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::bindings::Navigation;
+use crate::config::config_dir;
+use crate::picker;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use tracing::debug;
+
+// the items bindings were last run on, kept in the config directory so they can be picked again
+// stored as a json line each with the most recent last
+
+// older selections are dropped past this many
+const MAX_ENTRIES: usize = 200;
+
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
+pub struct Selection {
+    pub context: Option<String>,
+    pub namespace: Option<String>,
+    pub resource: String,
+    pub names: Vec<String>,
+}
+
+impl Selection {
+    // the listing of just the selected items
+    pub fn navigation(&self) -> Navigation {
+        Navigation {
+            resource: self.resource.clone(),
+            namespace: self.namespace.clone(),
+            names: self.names.clone(),
+            ..Default::default()
+        }
+    }
+
+    // the selection the way kubectl would be given it
+    // example: pods api-0 api-1 -n default --context prod
+    fn label(&self) -> String {
+        let mut label = format!("{} {}", self.resource, self.names.join(" "));
+        if let Some(namespace) = &self.namespace {
+            label.push_str(&format!(" -n {}", namespace));
+        }
+        if let Some(context) = &self.context {
+            label.push_str(&format!(" --context {}", context));
+        }
+        label
+    }
+}
+
+// every remembered selection, most recent first
+pub fn load() -> Vec<Selection> {
+    let path = match config_dir() {
+        Some(dir) => dir.join("history"),
+        None => return Vec::new(),
+    };
+    let contents = fs::read_to_string(path).unwrap_or_default();
+    contents
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+// remembers a selection as the most recent, moving it up if it was already there
+// failing to write only loses the history so it isn't an error
+pub fn record(selection: Selection) {
+    let dir = match config_dir() {
+        Some(dir) => dir,
+        None => return,
+    };
+
+    let mut selections: Vec<Selection> = load().into_iter().filter(|s| *s != selection).collect();
+    selections.truncate(MAX_ENTRIES - 1);
+    selections.insert(0, selection);
+
+    let lines: Vec<String> = selections
+        .iter()
+        .rev()
+        .filter_map(|s| serde_json::to_string(s).ok())
+        .collect();
+    let written = fs::create_dir_all(&dir)
+        .and_then(|_| fs::write(dir.join("history"), lines.join("\n") + "\n"));
+    if let Err(e) = written {
+        debug!(error = %e, "failed to write the history");
+    }
+}
+
+// asks which of the selections to list again
+pub fn pick(selections: &[Selection]) -> Option<Selection> {
+    let labels: Vec<String> = selections.iter().map(Selection::label).collect();
+    let chosen = picker::pick_one("recent", &labels)?;
+    let index = labels.iter().position(|l| *l == chosen)?;
+    selections.get(index).cloned()
+}
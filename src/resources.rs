@@ -0,0 +1,152 @@
+use crate::kubectl::{execute_cached, kubectl_base_cmd, kubectl_get_json};
+use crate::picker;
+use std::sync::Mutex;
+
+// a resource type the cluster serves, from kubectl api-resources
+#[derive(Clone)]
+pub struct ApiResource {
+    pub name: String,
+    pub short_names: Vec<String>,
+    pub api_version: String,
+    pub namespaced: bool,
+    pub kind: String,
+}
+
+// api-resources fetched once per run for resolving aliases
+// kubectl keeps its own discovery cache on disk so even the first lookup is quick
+static CACHE: Mutex<Option<Vec<ApiResource>>> = Mutex::new(None);
+
+// every resource type the cluster serves, including those from CRDs
+// a lookup like any other so it runs even in dry run mode
+pub fn list() -> Vec<ApiResource> {
+    let output = execute_cached(kubectl_base_cmd(None, "api-resources", None))
+        .filter(|o| o.exit_status.success())
+        .map(|o| o.stdout_str())
+        .unwrap_or_default();
+    let mut lines = output.lines();
+    let header = match lines.next() {
+        Some(header) => header,
+        None => return Vec::new(),
+    };
+
+    // short names are often blank so the columns are cut where the header puts them
+    // rather than split on whitespace
+    let starts: Vec<usize> = ["NAME", "SHORTNAMES", "APIVERSION", "NAMESPACED", "KIND"]
+        .iter()
+        .filter_map(|h| header.find(h))
+        .collect();
+    if starts.len() != 5 {
+        return Vec::new();
+    }
+
+    lines
+        .map(|line| {
+            let cell = |i: usize| {
+                let end = starts.get(i + 1).copied().unwrap_or(line.len());
+                line.get(starts[i]..end.min(line.len()))
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string()
+            };
+            ApiResource {
+                name: cell(0),
+                short_names: cell(1)
+                    .split(',')
+                    .filter(|s| !s.is_empty())
+                    .map(String::from)
+                    .collect(),
+                api_version: cell(2),
+                namespaced: cell(3) == "true",
+                kind: cell(4),
+            }
+        })
+        .filter(|r| !r.name.is_empty())
+        .collect()
+}
+
+// asks which resource type to list, showing what api-resources knows about each
+// returns None when nothing was chosen
+pub fn pick() -> Option<String> {
+    let resources = list();
+    let rows: Vec<String> = resources
+        .iter()
+        .map(|r| {
+            format!(
+                "{:<40} {:<12} {:<32} {:<32} {}",
+                r.name,
+                r.short_names.join(","),
+                r.kind,
+                r.api_version,
+                if r.namespaced {
+                    "namespaced"
+                } else {
+                    "cluster"
+                }
+            )
+        })
+        .collect();
+
+    let chosen = picker::pick_one("resource", &rows)?;
+    let index = rows.iter().position(|r| *r == chosen)?;
+    Some(resources[index].name.clone())
+}
+
+// asks which namespace to list in
+// returns None when nothing was chosen
+pub fn pick_namespace() -> Option<String> {
+    let namespaces: Vec<String> = kubectl_get_json(None, "namespaces", &[])?
+        .iter()
+        .filter_map(|n| n["metadata"]["name"].as_str().map(String::from))
+        .collect();
+    picker::pick_one("namespace", &namespaces)
+}
+
+// every name kubectl knows a resource type by, including the one given
+// example: deploy -> deployments, deployment, deploy, deployments.apps
+pub fn aliases(resource: &str) -> Vec<String> {
+    let mut cache = CACHE.lock().unwrap();
+    let resources = cache.get_or_insert_with(list);
+
+    let resource = resource.to_lowercase();
+    let mut aliases = vec![resource.clone()];
+    for r in resources.iter() {
+        let group = r.api_version.split_once('/').map(|(group, _)| group);
+        let forms: Vec<String> = [
+            Some(r.name.clone()),
+            Some(r.kind.to_lowercase()),
+            group.map(|g| format!("{}.{}", r.name, g)),
+        ]
+        .iter()
+        .flatten()
+        .cloned()
+        .chain(r.short_names.iter().cloned())
+        .collect();
+
+        if forms.contains(&resource) {
+            aliases.extend(forms);
+        }
+    }
+    aliases.sort();
+    aliases.dedup();
+    aliases
+}
+
+// whether items of the resource live in a namespace, assumed so for ones the cluster doesn't list
+pub fn is_namespaced(resource: &str) -> bool {
+    let aliases = aliases(resource);
+    let cache = CACHE.lock().unwrap();
+    cache
+        .iter()
+        .flatten()
+        .find(|r| aliases.contains(&r.name))
+        .map_or(true, |r| r.namespaced)
+}
+
+// whether a resource is one of a list of resource names in any of its forms
+// the list is checked as given first so the common case doesn't ask the cluster
+pub fn is_one_of(resource: &str, names: &[String]) -> bool {
+    if names.iter().any(|n| n == resource) {
+        return true;
+    }
+    aliases(resource).iter().any(|a| names.contains(a))
+}
@@ -0,0 +1,104 @@
+use crate::bindings::Script;
+use crate::config::config_dir;
+use std::fs;
+use std::io::ErrorKind;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+// how far into a script to look for the comments describing it
+const HEADER_LINES: usize = 20;
+
+// loads the executables in the bindings directory of the config as bindings
+// comments near the top of a script describe it
+//   # key: ctrl-r
+//   # description: Drain
+//   # accepts: nodes,node,no
+//   # mutates: false
+// or the file name gives the key and description as <key>_<description>, for example ctrl-r_drain.sh
+// files that aren't executable are skipped so notes can live alongside the scripts
+// a script could do anything so it counts as changing things unless its header says it doesn't
+pub fn load() -> Result<Vec<Script>, String> {
+    let dir = match config_dir() {
+        Some(dir) => dir.join("bindings"),
+        None => return Ok(Vec::new()),
+    };
+
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", dir.display(), e)),
+    };
+
+    let mut paths: Vec<_> = entries
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| is_executable(p))
+        .collect();
+    paths.sort();
+
+    paths.iter().map(|p| script(p)).collect()
+}
+
+fn script(path: &Path) -> Result<Script, String> {
+    // the file name is the fallback for the key and description
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    let (mut key, mut description) = match stem.split_once('_') {
+        Some((key, description)) => (key.to_string(), description.replace(['-', '_'], " ")),
+        None => (String::new(), stem.to_string()),
+    };
+    let mut accepts = Vec::new();
+    let mut mutates = true;
+
+    // binary executables have no header to read and rely on the file name
+    let contents = fs::read(path).unwrap_or_default();
+    for line in String::from_utf8_lossy(&contents)
+        .lines()
+        .take(HEADER_LINES)
+    {
+        if !(line.starts_with('#') || line.starts_with("//") || line.starts_with("--")) {
+            continue;
+        }
+        let comment = line.trim_start_matches(|c| c == '#' || c == '/' || c == '-');
+        let (field, value) = match comment.split_once(':') {
+            Some((field, value)) => (field.trim().to_lowercase(), value.trim()),
+            None => continue,
+        };
+        match field.as_str() {
+            "key" => key = value.to_string(),
+            "description" => description = value.to_string(),
+            "accepts" => {
+                accepts = value
+                    .split(',')
+                    .map(|r| r.trim().to_string())
+                    .filter(|r| !r.is_empty())
+                    .collect()
+            }
+            "mutates" => mutates = value != "false",
+            _ => {}
+        }
+    }
+
+    if key.is_empty() {
+        return Err(format!(
+            "No key for the binding {}, add a '# key: <key>' comment or name it <key>_<description>",
+            path.display()
+        ));
+    }
+
+    Ok(Script::new(
+        path.to_path_buf(),
+        key,
+        description,
+        accepts,
+        mutates,
+    ))
+}
+
+fn is_executable(path: &Path) -> bool {
+    fs::metadata(path).map_or(false, |m| {
+        m.is_file() && m.permissions().mode() & 0o111 != 0
+    })
+}
@@ -0,0 +1,70 @@
+use crate::bindings::Navigation;
+use crate::config::config_dir;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tracing::debug;
+
+// the listing the picker was last closed on, kept in the config directory so --resume can reopen it
+#[derive(Serialize, Deserialize, Default)]
+pub struct Session {
+    pub context: Option<String>,
+    pub namespace: Option<String>,
+    // sessions saved before -A was kept list from the namespace
+    #[serde(default)]
+    pub all_namespaces: bool,
+    pub resource: String,
+    pub names: Vec<String>,
+    pub selector: Option<String>,
+    pub field_selector: Option<String>,
+    pub query: Vec<String>,
+    pub get_args: Vec<String>,
+    #[serde(default)]
+    pub selected: Vec<String>,
+}
+
+impl Session {
+    pub fn navigation(self) -> Navigation {
+        Navigation {
+            resource: self.resource,
+            namespace: self.namespace,
+            all_namespaces: self.all_namespaces,
+            names: self.names,
+            selector: self.selector,
+            field_selector: self.field_selector,
+            query: self.query,
+            get_args: self.get_args,
+            selected: self.selected,
+            history: None,
+        }
+    }
+}
+
+// failing to save only means the next --resume opens an older session
+pub fn save(session: &Session) {
+    let path = match path() {
+        Some(path) => path,
+        None => return,
+    };
+    let written = serde_json::to_string(session)
+        .map_err(|e| e.to_string())
+        .and_then(|json| {
+            fs::create_dir_all(path.parent().unwrap_or(&path))
+                .and_then(|_| fs::write(&path, json))
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = written {
+        debug!(error = %e, "failed to save the session");
+    }
+}
+
+pub fn load() -> Result<Session, String> {
+    let path = path().ok_or("No session to resume")?;
+    let contents = fs::read_to_string(&path).map_err(|_| "No session to resume".to_string())?;
+    serde_json::from_str(&contents)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+fn path() -> Option<PathBuf> {
+    Some(config_dir()?.join("session"))
+}
@@ -0,0 +1,51 @@
+// widgets that open the picker on alt-k and insert the selected names at the cursor, like fzf's ctrl-t
+// when the line so far is a kubectl command with a resource, like `kubectl get deploy `, that resource is listed
+
+const BASH: &str = r#"__kubectl_select_widget() {
+    local words resource=pod selected
+    read -ra words <<< "${READLINE_LINE:0:READLINE_POINT}"
+    if [[ ${words[0]} =~ ^(kubectl|k)$ && ${#words[@]} -ge 3 && ${words[2]} != -* ]]; then
+        resource=${words[2]}
+    fi
+    selected=$(kubectl-select --delimiter ' ' "$resource" < /dev/tty) || return
+    READLINE_LINE="${READLINE_LINE:0:READLINE_POINT}${selected} ${READLINE_LINE:READLINE_POINT}"
+    READLINE_POINT=$((READLINE_POINT + ${#selected} + 1))
+}
+bind -m emacs-standard -x '"\ek": __kubectl_select_widget'
+"#;
+
+const ZSH: &str = r#"__kubectl_select_widget() {
+    local -a words=(${(z)LBUFFER})
+    local resource=pod selected
+    if [[ ${words[1]} == (kubectl|k) && ${#words} -ge 3 && ${words[3]} != -* ]]; then
+        resource=${words[3]}
+    fi
+    selected=$(kubectl-select --delimiter ' ' "$resource" < /dev/tty) && LBUFFER+="${selected} "
+    zle reset-prompt
+}
+zle -N __kubectl_select_widget
+bindkey '^[k' __kubectl_select_widget
+"#;
+
+const FISH: &str = r#"function __kubectl_select_widget
+    set -l words (commandline -opc)
+    set -l resource pod
+    if test (count $words) -ge 3; and contains -- $words[1] kubectl k; and not string match -q -- '-*' $words[3]
+        set resource $words[3]
+    end
+    set -l selected (kubectl-select --delimiter ' ' $resource < /dev/tty | string collect)
+    and commandline -i -- "$selected "
+    commandline -f repaint
+end
+bind \ek __kubectl_select_widget
+"#;
+
+// the widget for a shell, to be eval'd from its rc file
+pub fn script(shell: &str) -> &'static str {
+    match shell {
+        "bash" => BASH,
+        "zsh" => ZSH,
+        "fish" => FISH,
+        _ => unreachable!("clap only allows known shells"),
+    }
+}
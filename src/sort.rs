@@ -0,0 +1,106 @@
+use crate::age;
+use crate::kubectl::{header_columns, split_columns};
+use std::cmp::Ordering;
+
+// sorts listed rows by one of the header's columns, descending when the column starts with -
+// numbers and ages compare by value so 10 comes after 9 and 2d after 5h
+// example: -RESTARTS
+pub fn sort_rows(header: &str, rows: &mut [String], sort_by: &str) {
+    let (column, descending) = match sort_by.strip_prefix('-') {
+        Some(column) => (column, true),
+        None => (sort_by, false),
+    };
+    let index = match header_columns(header)
+        .iter()
+        .position(|h| h.eq_ignore_ascii_case(column))
+    {
+        Some(index) => index,
+        None => return,
+    };
+
+    // each row is split once up front rather than on every comparison
+    let mut keyed: Vec<(String, String)> = rows
+        .iter()
+        .map(|row| {
+            let cell = split_columns(header, row)
+                .into_iter()
+                .nth(index)
+                .unwrap_or_default();
+            (cell, row.clone())
+        })
+        .collect();
+
+    // stable so rows with the same value keep kubectl's order
+    keyed.sort_by(|(a, _), (b, _)| {
+        let ordering = compare(a, b);
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+    for (row, (_, sorted)) in rows.iter_mut().zip(keyed) {
+        *row = sorted;
+    }
+}
+
+// compares two cells as ages, then numbers, then plain text
+fn compare(a: &str, b: &str) -> Ordering {
+    if let (Some(a), Some(b)) = (age::parse(a), age::parse(b)) {
+        return a.cmp(&b);
+    }
+    if let (Some(a), Some(b)) = (leading_number(a), leading_number(b)) {
+        return a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+    }
+    a.cmp(b)
+}
+
+// the number a cell starts with, such as the count in a RESTARTS cell of 3 (5m ago)
+fn leading_number(cell: &str) -> Option<f64> {
+    let end = cell
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(cell.len());
+    cell[..end].parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_orders_ages_by_duration() {
+        assert_eq!(compare("5h", "2d"), Ordering::Less);
+        assert_eq!(compare("90s", "2m"), Ordering::Less);
+    }
+
+    #[test]
+    fn compare_orders_numbers_by_value() {
+        assert_eq!(compare("10", "9"), Ordering::Greater);
+        assert_eq!(compare("3 (5m ago)", "12"), Ordering::Less);
+    }
+
+    #[test]
+    fn compare_falls_back_to_text() {
+        assert_eq!(compare("Pending", "Running"), Ordering::Less);
+        assert_eq!(compare("<none>", "<none>"), Ordering::Equal);
+    }
+
+    #[test]
+    fn sort_rows_descending_by_column() {
+        let header = "NAME    RESTARTS";
+        let mut rows = vec![
+            "api-0   9".to_string(),
+            "api-1   10".to_string(),
+            "api-2   0".to_string(),
+        ];
+        sort_rows(header, &mut rows, "-restarts");
+        assert_eq!(rows, vec!["api-1   10", "api-0   9", "api-2   0"]);
+    }
+
+    #[test]
+    fn sort_rows_ignores_unknown_columns() {
+        let mut rows = vec!["b   1".to_string(), "a   2".to_string()];
+        sort_rows("NAME   READY", &mut rows, "AGE");
+        assert_eq!(rows, vec!["b   1", "a   2"]);
+    }
+}
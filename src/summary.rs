@@ -0,0 +1,85 @@
+use crate::bindings::BindingContext;
+use crate::kubectl::kubectl_get_json;
+use crate::resources;
+use serde_json::Value;
+
+// re-fetches the selected items and builds a one line summary of their current state
+// used after a mutating binding so there is feedback without re-running the tool
+// example: 3/3 nodes cordoned
+// example: deployment api: 4/5 ready
+// example: cronjob backup: suspended
+pub fn summarize(ctx: &BindingContext) -> Option<String> {
+    let items = kubectl_get_json(ctx.namespace.as_deref(), &ctx.resource, &ctx.names)?;
+
+    if resources::is_one_of(&ctx.resource, &BindingContext::accepts_nodes()) {
+        let cordoned = items
+            .iter()
+            .filter(|i| i["spec"]["unschedulable"].as_bool().unwrap_or(false))
+            .count();
+        return Some(format!("{}/{} nodes cordoned", cordoned, items.len()));
+    }
+
+    if resources::is_one_of(&ctx.resource, &BindingContext::accepts_pods()) {
+        let running = items
+            .iter()
+            .filter(|i| i["status"]["phase"].as_str() == Some("Running"))
+            .count();
+        return Some(format!("{}/{} pods running", running, items.len()));
+    }
+
+    if resources::is_one_of(&ctx.resource, &BindingContext::accepts_cronjobs()) {
+        let states: Vec<String> = items
+            .iter()
+            .map(|i| {
+                let suspended = i["spec"]["suspend"].as_bool().unwrap_or(false);
+                format!(
+                    "cronjob {}: {}",
+                    i["metadata"]["name"].as_str().unwrap_or_default(),
+                    if suspended { "suspended" } else { "active" }
+                )
+            })
+            .collect();
+        return Some(states.join(", "));
+    }
+
+    // workloads report their own readiness, anything else we can only say still exists
+    let workloads: Vec<String> = items
+        .iter()
+        .filter_map(|i| {
+            let (ready, desired) = readiness(i)?;
+            let paused = i["spec"]["paused"].as_bool().unwrap_or(false);
+            Some(format!(
+                "{} {}: {}/{} ready{}",
+                i["kind"].as_str().unwrap_or_default().to_lowercase(),
+                i["metadata"]["name"].as_str().unwrap_or_default(),
+                ready,
+                desired,
+                if paused { " (paused)" } else { "" }
+            ))
+        })
+        .collect();
+
+    if workloads.is_empty() {
+        Some(format!(
+            "{}/{} {} found",
+            items.len(),
+            ctx.names.len(),
+            ctx.resource
+        ))
+    } else {
+        Some(workloads.join(", "))
+    }
+}
+
+// ready and desired replica counts for anything that has them
+// deployments, statefulsets and replicasets use replicas while daemonsets count scheduled nodes
+fn readiness(item: &Value) -> Option<(u64, u64)> {
+    let status = &item["status"];
+    if let Some(desired) = item["spec"]["replicas"].as_u64() {
+        return Some((status["readyReplicas"].as_u64().unwrap_or(0), desired));
+    }
+
+    status["desiredNumberScheduled"]
+        .as_u64()
+        .map(|desired| (status["numberReady"].as_u64().unwrap_or(0), desired))
+}
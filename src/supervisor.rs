@@ -0,0 +1,377 @@
+use std::env;
+use std::fs::{self, DirBuilder, File};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::DirBuilderExt;
+use std::path::PathBuf;
+use std::process;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Once};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use subprocess::{CaptureData, Exec, ExitStatus, NullFile, Popen, Redirection};
+use tracing::debug;
+
+// keeps track of every kubectl child we spawn so none of them outlive us
+// children are reaped as soon as they finish and anything still running is killed on exit
+// children that don't need the terminal get a process group of their own, so stopping one also
+// stops whatever it started, like the commands a kubectl plugin or a shell runs
+
+// a child that is still running
+struct Tracked {
+    pid: u32,
+    // when it's stopped for running too long, pushed back by the grace period once terminated
+    deadline: Option<Instant>,
+    terminated: bool,
+}
+
+static CHILDREN: Mutex<Vec<Tracked>> = Mutex::new(Vec::new());
+
+// stops children that run past their deadline, started with the first child that has one
+static WATCHDOG: Once = Once::new();
+
+// a directory only we can use for the run's own files such as the proxy's kubeconfig, made when
+// first needed and removed along with the children
+static TEMP_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+// whether something is following a child and wants ctrl-c for itself rather than exiting
+static FOLLOWING: AtomicBool = AtomicBool::new(false);
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+// how long a child gets to exit after being terminated before it is killed
+const GRACE_PERIOD: Duration = Duration::from_secs(1);
+
+// sets up the ctrl-c handler for the whole process
+// ctrl-c stops following a child such as logs, otherwise it kills every child and exits
+pub fn install() {
+    ctrlc::set_handler(|| {
+        if FOLLOWING.load(Ordering::SeqCst) {
+            INTERRUPTED.store(true, Ordering::SeqCst);
+        } else {
+            shutdown();
+            std::process::exit(130);
+        }
+    })
+    .expect("Error setting Ctrl-C handler");
+}
+
+// spawns a long running child such as a watch
+// hand it back to release once done with it
+pub fn spawn(exec: Exec) -> Option<Popen> {
+    spawn_until(exec, None, true)
+}
+
+// spawns a child that is stopped once it has run for longer than the timeout
+// interactive children stay in our process group, which is the one the terminal is given to
+fn spawn_until(exec: Exec, timeout: Option<Duration>, grouped: bool) -> Option<Popen> {
+    debug!(command = %exec.to_cmdline_lossy(), ?timeout, "spawning");
+    let child = exec.popen().ok()?;
+    let pid = child.pid()?;
+    // set from here since Exec can't have the child do it, so anything it starts in the moment
+    // before is left in our group
+    if grouped {
+        unsafe {
+            libc::setpgid(pid as libc::pid_t, pid as libc::pid_t);
+        }
+    }
+    CHILDREN.lock().unwrap().push(Tracked {
+        pid,
+        deadline: timeout.map(|t| Instant::now() + t),
+        terminated: false,
+    });
+    if timeout.is_some() {
+        WATCHDOG.call_once(|| {
+            thread::spawn(watchdog);
+        });
+    }
+    Some(child)
+}
+
+// terminates children that are past their deadline, then kills those still running a grace
+// period later, leaving them for whoever is waiting on them to reap
+fn watchdog() {
+    loop {
+        thread::sleep(Duration::from_millis(50));
+        let now = Instant::now();
+        for child in CHILDREN.lock().unwrap().iter_mut() {
+            match child.deadline {
+                Some(deadline) if deadline <= now => {}
+                _ => continue,
+            }
+            if child.terminated {
+                debug!(pid = child.pid, "killing child past its deadline");
+                signal(child.pid, libc::SIGKILL);
+                child.deadline = None;
+            } else {
+                debug!(pid = child.pid, "terminating child past its deadline");
+                signal(child.pid, libc::SIGTERM);
+                child.deadline = Some(now + GRACE_PERIOD);
+                child.terminated = true;
+            }
+        }
+    }
+}
+
+// signals the process group a child leads, or just the child when it isn't leading one
+fn signal(pid: u32, signal: libc::c_int) {
+    let pid = pid as libc::pid_t;
+    unsafe {
+        if libc::kill(-pid, signal) != 0 {
+            libc::kill(pid, signal);
+        }
+    }
+}
+
+// whether a child was stopped by the watchdog rather than exiting by itself
+fn timed_out(started: Instant, timeout: Option<Duration>) -> bool {
+    timeout.map_or(false, |t| started.elapsed() >= t)
+}
+
+// stops a spawned child if it's still running and reaps it
+pub fn release(mut child: Popen) {
+    let pid = child.pid();
+    if let (None, Some(pid)) = (child.poll(), pid) {
+        signal(pid, libc::SIGTERM);
+        if let Ok(None) = child.wait_timeout(GRACE_PERIOD) {
+            signal(pid, libc::SIGKILL);
+        }
+    }
+    let _ = child.wait();
+    untrack(pid);
+}
+
+// runs a child to completion and captures its stdout and stderr, writing any input to its stdin
+// a child still running after the timeout is killed and nothing is returned
+pub fn capture(exec: Exec, input: Option<&str>, timeout: Option<Duration>) -> Option<CaptureData> {
+    let exec = match input {
+        Some(_) => exec.stdin(Redirection::Pipe),
+        None => exec,
+    };
+    let started = Instant::now();
+    let mut child = spawn_until(
+        exec.stdout(Redirection::Pipe).stderr(Redirection::Pipe),
+        timeout,
+        true,
+    )?;
+    let pid = child.pid();
+
+    // the pipes close once the watchdog has stopped its process group
+    let input = input.map(|i| i.as_bytes().to_vec());
+    let (stdout, stderr) = match child.communicate_start(input).read() {
+        Ok(output) => output,
+        Err(_) => {
+            release(child);
+            return None;
+        }
+    };
+
+    let exit_status = child.wait();
+    untrack(pid);
+    if timed_out(started, timeout) {
+        return None;
+    }
+
+    Some(CaptureData {
+        stdout: stdout.unwrap_or_default(),
+        stderr: stderr.unwrap_or_default(),
+        exit_status: exit_status.ok()?,
+    })
+}
+
+// runs a child to completion like capture, but reads its stdout and stderr as they come
+// each line is passed to on_line as it arrives, so a long command like a drain can be followed
+pub fn stream<F>(
+    exec: Exec,
+    input: Option<&str>,
+    timeout: Option<Duration>,
+    on_line: F,
+) -> Option<CaptureData>
+where
+    F: Fn(&str) + Send + Sync + 'static,
+{
+    let exec = match input {
+        Some(_) => exec.stdin(Redirection::Pipe),
+        None => exec,
+    };
+    let started = Instant::now();
+    let mut child = spawn_until(
+        exec.stdout(Redirection::Pipe).stderr(Redirection::Pipe),
+        timeout,
+        true,
+    )?;
+    let pid = child.pid();
+    let stdin = child.stdin.take();
+    let (stdout, stderr) = (child.stdout.take(), child.stderr.take());
+
+    // the readers aren't scoped to the call since a grandchild can keep the pipes open after the
+    // child is killed, and they're left to finish on their own rather than waited for then
+    let on_line = Arc::new(on_line);
+    let read = |pipe: Option<File>| {
+        let on_line = on_line.clone();
+        thread::spawn(move || {
+            let mut output = Vec::new();
+            let mut reader = match pipe {
+                Some(pipe) => BufReader::new(pipe),
+                None => return output,
+            };
+            let mut line = Vec::new();
+            while reader.read_until(b'\n', &mut line).map_or(false, |n| n > 0) {
+                on_line(String::from_utf8_lossy(&line).trim_end());
+                output.append(&mut line);
+            }
+            output
+        })
+    };
+    // closing stdin once written is what tells the child the input is done
+    if let (Some(mut stdin), Some(input)) = (stdin, input) {
+        let input = input.to_string();
+        thread::spawn(move || {
+            let _ = stdin.write_all(input.as_bytes());
+        });
+    }
+    let stdout = read(stdout);
+    let stderr = read(stderr);
+
+    let exit_status = child.wait();
+    untrack(pid);
+    let exit_status = match exit_status {
+        Ok(exit_status) if !timed_out(started, timeout) => exit_status,
+        _ => return None,
+    };
+
+    Some(CaptureData {
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+        exit_status,
+    })
+}
+
+// runs an interactive child attached to the terminal until it exits
+pub fn join(exec: Exec) -> Option<ExitStatus> {
+    let mut child = spawn_until(exec, None, false)?;
+    let pid = child.pid();
+    let exit_status = child.wait();
+    untrack(pid);
+    exit_status.ok()
+}
+
+// runs a child that reads input from stdin, like a clipboard command, until it exits
+// its output is discarded rather than piped since some fork into the background and keep pipes open
+pub fn feed(exec: Exec, input: &str) -> Option<ExitStatus> {
+    let mut child = spawn(
+        exec.stdin(Redirection::Pipe)
+            .stdout(NullFile)
+            .stderr(NullFile),
+    )?;
+    let pid = child.pid();
+
+    // closing stdin is what tells the child the input is done
+    let written = match child.stdin.take() {
+        Some(mut stdin) => stdin.write_all(input.as_bytes()).is_ok(),
+        None => false,
+    };
+    if !written {
+        release(child);
+        return None;
+    }
+
+    let exit_status = child.wait();
+    untrack(pid);
+    exit_status.ok()
+}
+
+// waits on a child that streams to the terminal like logs --follow
+// until it exits by itself or ctrl-c is pressed
+pub fn follow(mut child: Popen) -> Option<ExitStatus> {
+    INTERRUPTED.store(false, Ordering::SeqCst);
+    FOLLOWING.store(true, Ordering::SeqCst);
+
+    let exit_status = loop {
+        if let Ok(Some(exit_status)) = child.wait_timeout(Duration::from_millis(100)) {
+            break Some(exit_status);
+        }
+        if INTERRUPTED.load(Ordering::SeqCst) {
+            break None;
+        }
+    };
+
+    FOLLOWING.store(false, Ordering::SeqCst);
+    release(child);
+    exit_status
+}
+
+// follows several children at once like follow, until they have all exited or ctrl-c is pressed
+pub fn follow_all(mut children: Vec<Popen>) {
+    INTERRUPTED.store(false, Ordering::SeqCst);
+    FOLLOWING.store(true, Ordering::SeqCst);
+
+    while !INTERRUPTED.load(Ordering::SeqCst) {
+        if children.iter_mut().all(|c| c.poll().is_some()) {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    FOLLOWING.store(false, Ordering::SeqCst);
+    for child in children {
+        release(child);
+    }
+}
+
+// the run's private temp directory, which is new so nobody else can have put files in it
+// example: /tmp/kubectl-select-4321-1700000000123456789
+pub fn temp_dir() -> Option<PathBuf> {
+    let mut dir = TEMP_DIR.lock().unwrap();
+    if dir.is_none() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()?
+            .as_nanos();
+        let path = env::temp_dir().join(format!("kubectl-select-{}-{}", process::id(), nanos));
+        DirBuilder::new().mode(0o700).create(&path).ok()?;
+        *dir = Some(path);
+    }
+    dir.clone()
+}
+
+// terminates every child still running and reaps them
+// called on exit and on ctrl-c so nothing is left behind
+pub fn shutdown() {
+    if let Some(dir) = TEMP_DIR.lock().unwrap().take() {
+        let _ = fs::remove_dir_all(dir);
+    }
+    let children = std::mem::take(&mut *CHILDREN.lock().unwrap());
+
+    for child in &children {
+        signal(child.pid, libc::SIGTERM);
+    }
+
+    for child in children {
+        if !reaped_within(child.pid as libc::pid_t, GRACE_PERIOD) {
+            signal(child.pid, libc::SIGKILL);
+            unsafe {
+                libc::waitpid(child.pid as libc::pid_t, ptr::null_mut(), 0);
+            }
+        }
+    }
+}
+
+// the pid has to be taken before waiting since a finished child no longer reports it
+fn untrack(pid: Option<u32>) {
+    if let Some(pid) = pid {
+        CHILDREN.lock().unwrap().retain(|c| c.pid != pid);
+    }
+}
+
+// polls for a terminated child to exit so it doesn't linger as a zombie
+fn reaped_within(pid: libc::pid_t, timeout: Duration) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        let reaped = unsafe { libc::waitpid(pid, ptr::null_mut(), libc::WNOHANG) };
+        if reaped != 0 {
+            return true;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    false
+}
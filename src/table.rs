@@ -0,0 +1,381 @@
+use crate::age;
+use crate::bindings::BindingContext;
+use crate::kubectl::render_columns;
+use crate::resources;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+// lays out a listing from the items' json rather than from kubectl's printed table
+// the columns are the custom columns when there are some, otherwise kubectl's usual ones for
+// the resource types it knows or just NAME and AGE for everything else
+// example: NAME:.metadata.name,NODE:.spec.nodeName -> [NAME, NODE] api-0 node-a
+pub fn render(
+    items: &[Value],
+    resource: &str,
+    custom_columns: Option<&str>,
+    wide: bool,
+    show_labels: bool,
+) -> Vec<String> {
+    let columns = match custom_columns {
+        Some(spec) => custom(spec),
+        None => builtin(resource, wide),
+    };
+
+    let mut header: Vec<String> = columns.iter().map(|(name, _)| name.to_string()).collect();
+    if show_labels {
+        header.push("LABELS".into());
+    }
+
+    let mut table = vec![header];
+    for item in items {
+        let mut row: Vec<String> = columns.iter().map(|(_, cell)| cell.of(item)).collect();
+        if show_labels {
+            row.push(or_none(field(item, ".metadata.labels")));
+        }
+        table.push(row);
+    }
+    render_columns(&table)
+}
+
+// how a column gets its value out of an item
+enum Cell {
+    Path(String),
+    Computed(fn(&Value) -> String),
+}
+
+impl Cell {
+    fn of(&self, item: &Value) -> String {
+        match self {
+            Cell::Path(path) => or_none(field(item, path)),
+            Cell::Computed(f) => f(item),
+        }
+    }
+}
+
+// custom columns as kubectl takes them, HEADER:.json.path separated by commas
+fn custom(spec: &str) -> Vec<(String, Cell)> {
+    spec.split(',')
+        .filter_map(|c| c.split_once(':'))
+        .map(|(name, path)| (name.to_string(), Cell::Path(path.to_string())))
+        .collect()
+}
+
+// the columns kubectl prints for the resources that come up most
+fn builtin(resource: &str, wide: bool) -> Vec<(String, Cell)> {
+    let path = |name: &str, path: &str| (name.to_string(), Cell::Path(path.to_string()));
+    let computed = |name: &str, f: fn(&Value) -> String| (name.to_string(), Cell::Computed(f));
+
+    let mut columns = vec![path("NAME", ".metadata.name")];
+    if resources::is_one_of(resource, &BindingContext::accepts_pods()) {
+        columns.push(computed("READY", pod_ready));
+        columns.push(computed("STATUS", pod_status));
+        columns.push(computed("RESTARTS", pod_restarts));
+        columns.push(computed("AGE", age));
+        if wide {
+            columns.push(path("IP", ".status.podIP"));
+            columns.push(path("NODE", ".spec.nodeName"));
+        }
+    } else if resources::is_one_of(resource, &BindingContext::accepts_deployments()) {
+        columns.push(computed("READY", deployment_ready));
+        columns.push(computed("UP-TO-DATE", |i| {
+            i["status"]["updatedReplicas"]
+                .as_u64()
+                .unwrap_or(0)
+                .to_string()
+        }));
+        columns.push(computed("AVAILABLE", |i| {
+            i["status"]["availableReplicas"]
+                .as_u64()
+                .unwrap_or(0)
+                .to_string()
+        }));
+        columns.push(computed("AGE", age));
+        if wide {
+            columns.push(path("IMAGES", ".spec.template.spec.containers[*].image"));
+        }
+    } else if resources::is_one_of(resource, &BindingContext::accepts_nodes()) {
+        columns.push(computed("STATUS", node_status));
+        columns.push(computed("ROLES", node_roles));
+        columns.push(computed("AGE", age));
+        columns.push(path("VERSION", ".status.nodeInfo.kubeletVersion"));
+        if wide {
+            columns.push(path(
+                "INTERNAL-IP",
+                ".status.addresses[?(@.type==\"InternalIP\")].address",
+            ));
+        }
+    } else {
+        columns.push(computed("AGE", age));
+    }
+    columns
+}
+
+// the values at a custom columns style path, joined by commas when there are several
+// supports .field, [n] and [*], plus the [?(@.key=="value")] filter kubectl's jsonpath has
+// example: .spec.containers[*].image -> nginx:1.21,busybox
+fn field(item: &Value, path: &str) -> String {
+    let path = path.trim().trim_start_matches('{').trim_end_matches('}');
+
+    let mut values = vec![item];
+    for segment in segments(path) {
+        values = values.into_iter().flat_map(|v| step(v, &segment)).collect();
+    }
+
+    values
+        .into_iter()
+        .filter_map(display)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// splits a path into its fields and brackets
+// example: .spec.containers[0].image -> [spec, containers, [0], image]
+fn segments(path: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    for c in path.chars() {
+        match c {
+            '.' if depth == 0 => {
+                if !current.is_empty() {
+                    segments.push(std::mem::take(&mut current));
+                }
+            }
+            '[' if depth == 0 => {
+                if !current.is_empty() {
+                    segments.push(std::mem::take(&mut current));
+                }
+                depth += 1;
+                current.push(c);
+            }
+            ']' if depth == 1 => {
+                depth -= 1;
+                current.push(c);
+                segments.push(std::mem::take(&mut current));
+            }
+            '[' => {
+                depth += 1;
+                current.push(c);
+            }
+            ']' => {
+                depth -= 1;
+                current.push(c);
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        segments.push(current);
+    }
+    segments
+}
+
+fn step<'a>(value: &'a Value, segment: &str) -> Vec<&'a Value> {
+    let index = match segment.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+        Some(index) => index,
+        None => return value.get(segment).into_iter().collect(),
+    };
+    let elements = match value.as_array() {
+        Some(elements) => elements,
+        None => return Vec::new(),
+    };
+
+    if index == "*" {
+        return elements.iter().collect();
+    }
+    if let Some(filter) = index.strip_prefix("?(@.").and_then(|f| f.strip_suffix(')')) {
+        let (key, expected) = match filter.split_once("==") {
+            Some((key, expected)) => (key, expected.trim_matches(|c| c == '"' || c == '\'')),
+            None => return Vec::new(),
+        };
+        return elements
+            .iter()
+            .filter(|e| e.get(key).and_then(Value::as_str) == Some(expected))
+            .collect();
+    }
+    match index.parse::<i64>() {
+        // negative indexes count from the end like kubectl's jsonpath
+        Ok(i) if i < 0 => elements
+            .len()
+            .checked_sub(i.unsigned_abs() as usize)
+            .and_then(|i| elements.get(i))
+            .into_iter()
+            .collect(),
+        Ok(i) => elements.get(i as usize).into_iter().collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+// a value as a cell, maps like labels read key=value the way --show-labels prints them
+fn display(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        Value::Object(map) => Some(
+            map.iter()
+                .map(|(k, v)| format!("{}={}", k, v.as_str().unwrap_or_default()))
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        Value::Array(values) => Some(
+            values
+                .iter()
+                .filter_map(display)
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        other => Some(other.to_string()),
+    }
+}
+
+fn or_none(cell: String) -> String {
+    if cell.is_empty() {
+        "<none>".into()
+    } else {
+        cell
+    }
+}
+
+// how long ago the item was created, the way kubectl prints it
+fn age(item: &Value) -> String {
+    item["metadata"]["creationTimestamp"]
+        .as_str()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|created| {
+            let age = Utc::now().signed_duration_since(created);
+            age::format(age.to_std().unwrap_or_default())
+        })
+        .unwrap_or_else(|| "<unknown>".into())
+}
+
+fn pod_ready(pod: &Value) -> String {
+    let statuses = pod["status"]["containerStatuses"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let ready = statuses
+        .iter()
+        .filter(|s| s["ready"].as_bool().unwrap_or(false))
+        .count();
+    let total = pod["spec"]["containers"]
+        .as_array()
+        .map_or(statuses.len(), Vec::len);
+    format!("{}/{}", ready, total)
+}
+
+// the reason a container is waiting or terminated wins over the phase, like CrashLoopBackOff
+fn pod_status(pod: &Value) -> String {
+    if pod["metadata"]["deletionTimestamp"].is_string() {
+        return "Terminating".into();
+    }
+
+    let statuses = pod["status"]["containerStatuses"].as_array();
+    for status in statuses.into_iter().flatten() {
+        for state in &["waiting", "terminated"] {
+            if let Some(reason) = status["state"][state]["reason"].as_str() {
+                return reason.into();
+            }
+        }
+    }
+
+    pod["status"]["reason"]
+        .as_str()
+        .or_else(|| pod["status"]["phase"].as_str())
+        .unwrap_or("Unknown")
+        .into()
+}
+
+fn pod_restarts(pod: &Value) -> String {
+    pod["status"]["containerStatuses"]
+        .as_array()
+        .map_or(0, |statuses| {
+            statuses
+                .iter()
+                .filter_map(|s| s["restartCount"].as_u64())
+                .sum()
+        })
+        .to_string()
+}
+
+fn deployment_ready(deployment: &Value) -> String {
+    format!(
+        "{}/{}",
+        deployment["status"]["readyReplicas"].as_u64().unwrap_or(0),
+        deployment["spec"]["replicas"].as_u64().unwrap_or(0)
+    )
+}
+
+fn node_status(node: &Value) -> String {
+    let ready = node["status"]["conditions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|c| c["type"] == "Ready")
+        .map_or(false, |c| c["status"] == "True");
+    let mut status = if ready { "Ready" } else { "NotReady" }.to_string();
+    if node["spec"]["unschedulable"].as_bool().unwrap_or(false) {
+        status.push_str(",SchedulingDisabled");
+    }
+    status
+}
+
+fn node_roles(node: &Value) -> String {
+    let roles: Vec<&str> = node["metadata"]["labels"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(k, _)| k.strip_prefix("node-role.kubernetes.io/"))
+        .collect();
+    or_none(roles.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn pod() -> Value {
+        json!({
+            "metadata": {"name": "api-0", "labels": {"app": "api"}},
+            "spec": {"containers": [
+                {"name": "app", "image": "nginx:1.21"},
+                {"name": "sidecar", "image": "busybox"},
+            ]},
+            "status": {"conditions": [
+                {"type": "Initialized", "status": "True"},
+                {"type": "Ready", "status": "False"},
+            ]},
+        })
+    }
+
+    #[test]
+    fn field_follows_paths() {
+        assert_eq!(field(&pod(), ".metadata.name"), "api-0");
+        assert_eq!(field(&pod(), "{.metadata.name}"), "api-0");
+        assert_eq!(field(&pod(), ".spec.nodeName"), "");
+    }
+
+    #[test]
+    fn field_indexes_arrays() {
+        assert_eq!(field(&pod(), ".spec.containers[0].name"), "app");
+        assert_eq!(field(&pod(), ".spec.containers[-1].name"), "sidecar");
+        assert_eq!(field(&pod(), ".spec.containers[2].name"), "");
+        assert_eq!(
+            field(&pod(), ".spec.containers[*].image"),
+            "nginx:1.21,busybox"
+        );
+    }
+
+    #[test]
+    fn field_filters_arrays() {
+        assert_eq!(
+            field(&pod(), r#".status.conditions[?(@.type=="Ready")].status"#),
+            "False"
+        );
+    }
+
+    #[test]
+    fn field_shows_maps_as_key_value() {
+        assert_eq!(field(&pod(), ".metadata.labels"), "app=api");
+    }
+}
@@ -0,0 +1,139 @@
+use serde::Deserialize;
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+// colors for skim and the binding list in the preview
+// a preset picks all of them and the config can override each one
+// [theme]
+// preset = "light"
+// skim = "light,hl:160"
+// description = "blue"
+// key = "208"
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ThemeConfig {
+    pub preset: Option<String>,
+    // passed straight to skim's --color
+    pub skim: Option<String>,
+    // ansi color names like red or bright-blue, or a 256 color number
+    pub description: Option<String>,
+    pub key: Option<String>,
+}
+
+pub const PRESETS: [&str; 3] = ["dark", "light", "solarized"];
+
+pub struct Theme {
+    pub skim: String,
+    description: String,
+    key: String,
+}
+
+// set once at startup so previews don't need to be handed the theme
+static THEME: Mutex<Option<Theme>> = Mutex::new(None);
+
+// with --no-color or NO_COLOR nothing is colored whatever the theme says
+static NO_COLOR: AtomicBool = AtomicBool::new(false);
+
+impl Theme {
+    // the colors of a preset by name
+    fn preset(name: &str) -> Option<Theme> {
+        let (skim, description, key) = match name {
+            "dark" => ("dark", "red", "yellow"),
+            "light" => ("light", "blue", "magenta"),
+            "solarized" => (
+                "fg:240,bg:-1,hl:33,fg+:241,bg+:-1,hl+:33,info:33,prompt:33,pointer:166,marker:166,spinner:33,header:136",
+                "33",
+                "136",
+            ),
+            _ => return None,
+        };
+        Some(Theme {
+            skim: skim.into(),
+            description: escape(description)?,
+            key: escape(key)?,
+        })
+    }
+
+    // the theme from a preset, the --theme flag winning over the config, and any overrides from the config
+    pub fn resolve(flag: Option<&str>, config: &ThemeConfig) -> Result<Theme, String> {
+        let name = flag.or(config.preset.as_deref()).unwrap_or("dark");
+        let mut theme = Theme::preset(name).ok_or_else(|| {
+            format!(
+                "Unknown theme {}, expected one of {}",
+                name,
+                PRESETS.join(", ")
+            )
+        })?;
+
+        if let Some(skim) = &config.skim {
+            theme.skim = skim.clone();
+        }
+        if let Some(color) = &config.description {
+            theme.description =
+                escape(color).ok_or_else(|| format!("Unknown theme color {}", color))?;
+        }
+        if let Some(color) = &config.key {
+            theme.key = escape(color).ok_or_else(|| format!("Unknown theme color {}", color))?;
+        }
+
+        Ok(theme)
+    }
+}
+
+pub fn set(theme: Theme) {
+    *THEME.lock().unwrap() = Some(theme);
+}
+
+// turns color off everywhere, following https://no-color.org when the flag isn't given
+pub fn set_no_color(flag: bool) {
+    let from_env = env::var_os("NO_COLOR").map_or(false, |v| !v.is_empty());
+    NO_COLOR.store(flag || from_env, Ordering::SeqCst);
+}
+
+// whether anything should be colored at all
+pub fn colored() -> bool {
+    !NO_COLOR.load(Ordering::SeqCst)
+}
+
+// the skim color option of the current theme
+pub fn skim_colors() -> String {
+    if !colored() {
+        return "bw".into();
+    }
+    with_theme(|t| t.skim.clone())
+}
+
+// a row of the binding list shown in the preview
+pub fn binding_preview(description: &str, key: &str) -> String {
+    if !colored() {
+        return format!("{}\t{}", description, key);
+    }
+    with_theme(|t| format!("{}{}\t{}{}\x1b[0m", t.description, description, t.key, key))
+}
+
+fn with_theme<T>(f: impl FnOnce(&Theme) -> T) -> T {
+    let theme = THEME.lock().unwrap();
+    match theme.as_ref() {
+        Some(theme) => f(theme),
+        None => f(&Theme::preset("dark").unwrap()),
+    }
+}
+
+// the escape sequence for a color name or 256 color number
+fn escape(color: &str) -> Option<String> {
+    const NAMES: [&str; 8] = [
+        "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    ];
+
+    if let Ok(n) = color.parse::<u8>() {
+        return Some(format!("\x1b[38;5;{}m", n));
+    }
+    let (bright, name) = match color.strip_prefix("bright-") {
+        Some(name) => (true, name),
+        None => (false, color),
+    };
+    let index = NAMES.iter().position(|n| *n == name)?;
+    let base = if bright { 90 } else { 30 };
+    Some(format!("\x1b[{}m", base + index))
+}
@@ -0,0 +1,77 @@
+use crate::kubectl::shell_quote;
+use crate::supervisor;
+use std::env;
+use std::fs;
+use subprocess::{Exec, ExitStatus};
+
+// runs the picker in a tmux popup instead of inline, like fzf-tmux
+// the tool reruns itself in the popup with the same arguments, writing its output and exit code
+// to files that are handed back to the invoking shell once the popup closes
+
+// how much of the window the popup or split takes up
+const POPUP_SIZE: &str = "80%";
+const SPLIT_SIZE: &str = "40%";
+
+// whether we are inside tmux at all, otherwise --tmux is ignored and the picker runs inline
+pub fn available() -> bool {
+    env::var_os("TMUX").map_or(false, |t| !t.is_empty())
+}
+
+// reruns the tool inside tmux without --tmux and prints what it printed
+// tmux older than 3.2 has no popups so a split is used instead
+// returns the exit code of the rerun
+pub fn rerun() -> Option<i32> {
+    let dir = supervisor::temp_dir()?;
+    let output_path = dir.join("output");
+    let status_path = dir.join("status");
+
+    let args: Vec<String> = env::args().skip(1).filter(|a| a != "--tmux").collect();
+    let rerun = Exec::cmd(env::current_exe().ok()?).args(&args);
+
+    // the popup runs in the tmux server's environment so carry over what kubectl depends on
+    let mut script = format!("cd {} && ", shell_quote(env::current_dir().ok()?));
+    for var in &["KUBECONFIG", "PAGER"] {
+        if let Ok(value) = env::var(var) {
+            script.push_str(&format!("export {}={}; ", var, shell_quote(value)));
+        }
+    }
+    script.push_str(&format!(
+        "{} > {}; echo $? > {}",
+        rerun.to_cmdline_lossy(),
+        shell_quote(&output_path),
+        shell_quote(&status_path)
+    ));
+
+    let popup = supervisor::join(
+        Exec::cmd("tmux")
+            .arg("display-popup")
+            .arg("-E")
+            .arg("-w")
+            .arg(POPUP_SIZE)
+            .arg("-h")
+            .arg(POPUP_SIZE)
+            .arg(&script),
+    );
+    if !matches!(popup, Some(ExitStatus::Exited(0))) && !status_path.exists() {
+        // splits return straight away so wait on a channel the script signals when done
+        let channel = format!("kubectl-select-{}", std::process::id());
+        supervisor::join(
+            Exec::cmd("tmux")
+                .arg("split-window")
+                .arg("-v")
+                .arg("-l")
+                .arg(SPLIT_SIZE)
+                .arg(format!("{}; tmux wait-for -S {}", script, channel)),
+        )?;
+        supervisor::join(Exec::cmd("tmux").arg("wait-for").arg(channel))?;
+    }
+
+    let output = fs::read_to_string(&output_path).unwrap_or_default();
+    let code = fs::read_to_string(&status_path)
+        .ok()
+        .and_then(|s| s.trim().parse().ok());
+    let _ = fs::remove_dir_all(&dir);
+
+    print!("{}", output);
+    code
+}